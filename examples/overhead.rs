@@ -0,0 +1,235 @@
+//! Measure the per-read overhead of the timer backends, and of the
+//! different fence/barrier variants of the raw CPU counter read, on
+//! the current machine
+//!
+//! Run with (preferably in release):
+//!
+//! ```text
+//! cargo run --release --example overhead
+//! ```
+//!
+//! Each variant is read back-to-back many times; the distribution of
+//! the deltas between consecutive reads is then summarized as a
+//! percentile table, in the style of the tables in the crate
+//! documentation. The average is calculated over the fastest 95% of
+//! reads, as beyond that the outliers should be ignored.
+//!
+//! Note that the values for the std::time backend (and for the
+//! `Instant::now` variant) are in nanoseconds, and the values for all
+//! the other variants are in CPU counter ticks.
+//!
+//! With the `vdso` feature on Linux the std::time backend calls
+//! `clock_gettime` directly, and this can be compared with the
+//! `Instant::now` variant:
+//!
+//! ```text
+//! cargo run --release --example overhead --features vdso
+//! ```
+
+//a Imports
+use cpu_timer::{TArch, TDesc, Timer};
+
+//a Constants
+/// Number of back-to-back reads to make for each variant
+const NUM_READS: usize = 100_000;
+
+/// Percentiles to report in the table
+const PERCENTILES: [usize; 7] = [10, 25, 50, 75, 90, 95, 99];
+
+//a Types
+/// A named raw counter read variant
+type Variant = (&'static str, fn() -> u64);
+
+//a Raw counter reads
+//fi Raw counter reads for x86_64
+#[cfg(target_arch = "x86_64")]
+mod raw {
+    use std::arch::asm;
+
+    /// `rdtsc` with no fences; this may be reordered with respect to
+    /// surrounding instructions
+    #[inline(always)]
+    pub fn rdtsc() -> u64 {
+        let lo: u64;
+        let hi: u64;
+        unsafe {
+            asm!("rdtsc", lateout("eax") lo, lateout("edx") hi, options(nomem, nostack));
+        }
+        hi << 32 | lo
+    }
+
+    /// `lfence` then `rdtsc`; all preceding instructions complete
+    /// (locally) before the counter is read
+    #[inline(always)]
+    pub fn lfence_rdtsc() -> u64 {
+        let lo: u64;
+        let hi: u64;
+        unsafe {
+            asm!("lfence", "rdtsc", lateout("eax") lo, lateout("edx") hi, options(nostack));
+        }
+        hi << 32 | lo
+    }
+
+    /// `mfence`, `lfence` then `rdtsc`; all preceding loads and
+    /// stores are globally visible before the counter is read
+    #[inline(always)]
+    pub fn mfence_rdtsc() -> u64 {
+        let lo: u64;
+        let hi: u64;
+        unsafe {
+            asm!("mfence", "lfence", "rdtsc", lateout("eax") lo, lateout("edx") hi, options(nostack));
+        }
+        hi << 32 | lo
+    }
+
+    /// `rdtscp`, which waits for preceding instructions before reading
+    #[inline(always)]
+    pub fn rdtscp() -> u64 {
+        let lo: u64;
+        let hi: u64;
+        unsafe {
+            asm!("rdtscp", lateout("eax") lo, lateout("edx") hi, lateout("ecx") _, options(nomem, nostack));
+        }
+        hi << 32 | lo
+    }
+
+    /// The variants to measure
+    pub const VARIANTS: &[super::Variant] = &[
+        ("rdtsc", rdtsc),
+        ("lfence; rdtsc", lfence_rdtsc),
+        ("mfence; lfence; rdtsc", mfence_rdtsc),
+        ("rdtscp", rdtscp),
+    ];
+}
+
+//fi Raw counter reads for aarch64
+#[cfg(target_arch = "aarch64")]
+mod raw {
+    use std::arch::asm;
+
+    /// `mrs cntvct_el0` with no barrier; this may be executed out of
+    /// order with respect to surrounding instructions
+    #[inline(always)]
+    pub fn mrs() -> u64 {
+        let timer: u64;
+        unsafe {
+            asm!("mrs {timer}, cntvct_el0", timer = out(reg) timer, options(nomem, nostack));
+        }
+        timer
+    }
+
+    /// `isb` then `mrs cntvct_el0`; the instruction stream is
+    /// synchronized before the counter is read
+    #[inline(always)]
+    pub fn isb_mrs() -> u64 {
+        let timer: u64;
+        unsafe {
+            asm!("isb", "mrs {timer}, cntvct_el0", timer = out(reg) timer, options(nostack));
+        }
+        timer
+    }
+
+    /// The variants to measure
+    pub const VARIANTS: &[super::Variant] = &[("mrs", mrs), ("isb; mrs", isb_mrs)];
+}
+
+//fi Raw counter reads for other architectures
+#[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64")))]
+mod raw {
+    /// There are no raw variants on this architecture
+    pub const VARIANTS: &[super::Variant] = &[];
+}
+
+//fi instant_nanos
+/// `std::time::Instant::now`, as nanoseconds since the first call
+fn instant_nanos() -> u64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    let epoch = *EPOCH.get_or_init(std::time::Instant::now);
+    (std::time::Instant::now() - epoch).as_nanos() as u64
+}
+
+//a Measurement
+//tp Summary
+/// The summary of a distribution of read-to-read deltas
+struct Summary {
+    percentiles: Vec<u64>,
+    max: u64,
+    average_to_95: u64,
+}
+
+//fp summarize
+/// Summarize a set of consecutive counter readings
+fn summarize(record: &[u64]) -> Summary {
+    let mut deltas: Vec<u64> = record.windows(2).map(|w| w[1].wrapping_sub(w[0])).collect();
+    deltas.sort_unstable();
+    let n = deltas.len();
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|p| deltas[((n * p) / 100).min(n - 1)])
+        .collect();
+    let max = deltas[n - 1];
+    let n_95 = (n * 95) / 100;
+    let average_to_95 = deltas[0..n_95].iter().sum::<u64>() / (n_95 as u64);
+    Summary {
+        percentiles,
+        max,
+        average_to_95,
+    }
+}
+
+//fp measure_fn
+/// Measure the distribution of back-to-back reads of a raw counter
+fn measure_fn(f: fn() -> u64) -> Summary {
+    let mut record = vec![0u64; NUM_READS];
+    for r in record.iter_mut() {
+        *r = f();
+    }
+    summarize(&record)
+}
+
+//fp measure_timer
+/// Measure the distribution of back-to-back reads of a [Timer]
+fn measure_timer<const S: bool>() -> Summary
+where
+    TDesc<S>: TArch,
+{
+    let mut record = vec![0u64; NUM_READS];
+    let mut t = Timer::<S>::default();
+    t.start();
+    for r in record.iter_mut() {
+        *r = t.elapsed();
+    }
+    summarize(&record)
+}
+
+//a Main
+//fp main
+fn main() {
+    let mut results = vec![
+        ("Timer<true> (asm)", measure_timer::<true>()),
+        ("Timer<false> (std::time)", measure_timer::<false>()),
+        ("Instant::now", measure_fn(instant_nanos)),
+    ];
+    for (name, f) in raw::VARIANTS {
+        results.push((name, measure_fn(*f)));
+    }
+
+    let width = results.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+    print!("| {:width$} |", "variant");
+    for p in PERCENTILES {
+        print!(" {p:>6}% |");
+    }
+    println!("    100% | avg 95% |");
+    print!("|-{}-|", "-".repeat(width));
+    for _ in 0..PERCENTILES.len() + 2 {
+        print!("---------|");
+    }
+    println!();
+    for (name, s) in &results {
+        print!("| {name:width$} |");
+        for v in &s.percentiles {
+            print!(" {v:>7} |");
+        }
+        println!(" {:>7} | {:>7} |", s.max, s.average_to_95);
+    }
+}
@@ -0,0 +1,171 @@
+//a Imports
+use crate::acc_vec::fmt_acc_cnt;
+use crate::{BaseTimer, Startable, TArch, TDesc, TraceCount, TraceValue};
+
+//a AccArray2D
+//tp AccArray2D
+/// An [AccArray2D] is a two-dimensional [AccArray], accumulating the
+/// times taken to execute regions of code identified by a row and a
+/// column, from a common start point
+///
+/// This suits regions that are naturally two-dimensional, such as the
+/// time spent by each pass of a compiler on each kind of node, without
+/// the index arithmetic of a flat [AccArray].
+///
+/// As for an [AccArray] the accumulated values and counts saturate,
+/// and float accumulators are compensated.
+///
+/// [AccArray]: crate::AccArray
+///
+/// ```
+/// # use cpu_timer::AccArray2D;
+/// const PASSES: usize = 2;
+/// const KINDS: usize = 3;
+/// let mut t = AccArray2D::<true, u64, u64, PASSES, KINDS>::default();
+/// for pass in 0..PASSES {
+///     for kind in [0, 2, 1, 2] {
+///         t.start();
+///         // do something!
+///         t.acc_rc(pass, kind);
+///     }
+/// }
+/// assert_eq!(t.cnts()[1], [1, 1, 2]);
+/// println!("{t}");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AccArray2D<
+    const S: bool,
+    T: TraceValue,
+    C: TraceCount,
+    const R: usize,
+    const COLS: usize,
+    A: TArch = TDesc<S>,
+> {
+    base: BaseTimer<S, A>,
+    accs: [[T; COLS]; R],
+    comps: [[T::Comp; COLS]; R],
+    cnts: [[C; COLS]; R],
+}
+
+//ip Default for AccArray2D
+impl<const S: bool, T, C, const R: usize, const COLS: usize, A> core::default::Default
+    for AccArray2D<S, T, C, R, COLS, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn default() -> Self {
+        Self {
+            base: BaseTimer::default(),
+            accs: [[T::default(); COLS]; R],
+            comps: [[T::Comp::default(); COLS]; R],
+            cnts: [[C::default(); COLS]; R],
+        }
+    }
+}
+
+//ip Display for AccArray2D
+impl<const S: bool, T, C, const R: usize, const COLS: usize, A> core::fmt::Display
+    for AccArray2D<S, T, C, R, COLS, A>
+where
+    A: TArch,
+    T: TraceValue + core::fmt::Display + core::ops::Div<C>,
+    <T as core::ops::Div<C>>::Output: core::fmt::Display,
+    C: TraceCount + core::fmt::Display + PartialEq<C>,
+{
+    /// Display each row on a separate line, in the same form as an
+    /// [crate::AccArray]
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        for r in 0..R {
+            if r != 0 {
+                writeln!(fmt)?;
+            }
+            write! {fmt, "["}?;
+            for c in 0..COLS {
+                if c != 0 {
+                    write! {fmt, ", "}?;
+                }
+                fmt_acc_cnt(fmt, self.accs[r][c], self.cnts[r][c], 0, None)?;
+            }
+            write! {fmt, "]"}?;
+        }
+        Ok(())
+    }
+}
+
+//ip AccArray2D
+impl<const S: bool, T, C, const R: usize, const COLS: usize, A> AccArray2D<S, T, C, R, COLS, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    //mp clear
+    /// Clear the timer and accumulated values
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    //mp start
+    /// Start the underlying timer
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.base.start();
+    }
+
+    //mp acc_rc
+    /// Add the ticks on exit to a specific region
+    ///
+    /// If the row or column is out of range then this does nothing
+    #[inline(always)]
+    pub fn acc_rc(&mut self, row: usize, col: usize) {
+        if row < R && col < COLS {
+            let delta: u64 = self.base.elapsed();
+            self.accs[row][col] =
+                self.accs[row][col].sat_add_comp(delta, &mut self.comps[row][col]);
+            self.cnts[row][col].sat_inc();
+        }
+    }
+
+    //mp acc_rc_restart
+    /// Add the ticks on exit to a specific region, and restart the
+    /// timer
+    ///
+    /// If the row or column is out of range then this just restarts
+    /// the timer
+    #[inline(always)]
+    pub fn acc_rc_restart(&mut self, row: usize, col: usize) {
+        let delta = self.base.elapsed_and_update();
+        if row < R && col < COLS {
+            self.accs[row][col] =
+                self.accs[row][col].sat_add_comp(delta, &mut self.comps[row][col]);
+            self.cnts[row][col].sat_inc();
+        }
+    }
+
+    //ap accs
+    /// Return the accumulated values, by row
+    pub fn accs(&self) -> &[[T; COLS]; R] {
+        &self.accs
+    }
+
+    //ap cnts
+    /// Return the accumulated counts, by row
+    pub fn cnts(&self) -> &[[C; COLS]; R] {
+        &self.cnts
+    }
+}
+
+//ip Startable for AccArray2D
+impl<const S: bool, T, C, const R: usize, const COLS: usize, A> Startable<S, A>
+    for AccArray2D<S, T, C, R, COLS, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+    }
+}
@@ -1,5 +1,147 @@
 //a Imports
-use crate::{BaseTimer, TArch, TDesc, TraceCount, TraceValue};
+#[cfg(feature = "std")]
+use crate::hist::nearest_rank;
+#[cfg(feature = "metrics")]
+use crate::utils::emit_metric;
+use crate::utils::percentage;
+#[cfg(feature = "json")]
+use crate::utils::to_json;
+#[cfg(feature = "csv")]
+use crate::utils::write_csv;
+#[cfg(feature = "std")]
+use crate::Delta;
+#[cfg(feature = "std")]
+use crate::TimingReport;
+use crate::{BaseTimer, RecordDelta, Startable, TArch, TDesc, TraceCount, TraceValue};
+
+//a Useful functions
+//fi fmt_acc_cnt
+/// Format an accumulated value, count and average for an AccArray or
+/// AccVec entry, and optionally the range of the deltas
+///
+/// With a precision of 0 the average is the value divided by the
+/// count (so integer-truncated for integer types), otherwise it is
+/// calculated in floating point and shown with *precision* decimals
+pub(crate) fn fmt_acc_cnt<T, C>(
+    fmt: &mut core::fmt::Formatter,
+    acc: T,
+    cnt: C,
+    precision: u8,
+    range: Option<(T, T)>,
+) -> Result<(), core::fmt::Error>
+where
+    T: TraceValue + core::fmt::Display + core::ops::Div<C>,
+    <T as core::ops::Div<C>>::Output: core::fmt::Display,
+    C: TraceCount + core::fmt::Display + PartialEq<C>,
+{
+    if cnt == C::default() {
+        return write!(fmt, "({acc}, {cnt}, -)");
+    } else if precision == 0 {
+        write!(fmt, "({}, {}, {}", acc, cnt, acc / cnt)?;
+    } else {
+        let avg = acc.as_f64() / cnt.as_f64();
+        write!(fmt, "({}, {}, {:.*}", acc, cnt, precision as usize, avg)?;
+    }
+    if let Some((min, max)) = range {
+        write!(fmt, ", {min}..{max}")?;
+    }
+    write!(fmt, ")")
+}
+
+//a AccStat
+//tp AccStat
+/// The statistics of one entry of an [AccArray] or [AccVec], as
+/// returned by their `iter_stats` methods
+pub struct AccStat<T, C>
+where
+    T: core::ops::Div<C>,
+{
+    /// The index of the entry
+    pub index: usize,
+    /// The accumulated value
+    pub sum: T,
+    /// The count
+    pub count: C,
+    /// The average (sum divided by count), or None if the count is zero
+    pub avg: Option<<T as core::ops::Div<C>>::Output>,
+}
+
+//ip Debug for AccStat
+impl<T, C> core::fmt::Debug for AccStat<T, C>
+where
+    T: core::ops::Div<C> + core::fmt::Debug,
+    C: core::fmt::Debug,
+    <T as core::ops::Div<C>>::Output: core::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        fmt.debug_struct("AccStat")
+            .field("index", &self.index)
+            .field("sum", &self.sum)
+            .field("count", &self.count)
+            .field("avg", &self.avg)
+            .finish()
+    }
+}
+
+//ip Clone for AccStat
+impl<T, C> core::clone::Clone for AccStat<T, C>
+where
+    T: core::ops::Div<C> + Clone,
+    C: Clone,
+    <T as core::ops::Div<C>>::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index,
+            sum: self.sum.clone(),
+            count: self.count.clone(),
+            avg: self.avg.clone(),
+        }
+    }
+}
+
+//ip Copy for AccStat
+impl<T, C> core::marker::Copy for AccStat<T, C>
+where
+    T: core::ops::Div<C> + Copy,
+    C: Copy,
+    <T as core::ops::Div<C>>::Output: Copy,
+{
+}
+
+//ip PartialEq for AccStat
+impl<T, C> core::cmp::PartialEq for AccStat<T, C>
+where
+    T: core::ops::Div<C> + PartialEq,
+    C: PartialEq,
+    <T as core::ops::Div<C>>::Output: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+            && self.sum == other.sum
+            && self.count == other.count
+            && self.avg == other.avg
+    }
+}
+
+//ip AccStat
+impl<T, C> AccStat<T, C>
+where
+    T: TraceValue + core::ops::Div<C>,
+    C: TraceCount + PartialEq<C>,
+{
+    //cp new
+    /// Create the statistics for an entry from its value and count
+    fn new(index: usize, sum: T, count: C) -> Self {
+        let avg = (count != C::default()).then(|| sum / count);
+        Self {
+            index,
+            sum,
+            count,
+            avg,
+        }
+    }
+}
 
 //a AccArray
 //tp AccArray
@@ -28,78 +170,429 @@ use crate::{BaseTimer, TArch, TDesc, TraceCount, TraceValue};
 /// An AccArray can be generated for any N, for an accumulator value
 /// of (), u8, u16, u32, u64, u128 and usize, and for a counter value
 /// of (), u8, u16, u32, u64, usize. If a value of () is used then the
-/// count or delta accumulator are effectively always 0.
+/// count or delta accumulator are effectively always 0. The
+/// accumulated values and counts saturate at the maximum of their
+/// types rather than wrapping.
+///
+/// For an accumulator value of f32 or f64 the deltas are accumulated
+/// with compensated (Kahan) summation, keeping a compensation term
+/// for each entry, so that adding millions of small deltas to a large
+/// total does not drift in its low bits; integer accumulators are
+/// exact, and have no compensation term.
+///
+/// An outlier threshold may be set, in which case any single delta
+/// exceeding the threshold (such as one including a scheduler
+/// interruption or a thread migration) is neither accumulated nor
+/// counted, and the number of such rejected deltas is recorded
+/// instead; this applies the outlier rejection of the methodology in
+/// the crate documentation to a running accumulation.
 #[derive(Debug, Clone, Copy)]
-pub struct AccArray<const S: bool, T: TraceValue, C: TraceCount, const N: usize>
-where
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct AccArray<
+    const S: bool,
+    T: TraceValue,
+    C: TraceCount,
+    const N: usize,
+    A: TArch = TDesc<S>,
+> {
+    base: BaseTimer<S, A>,
     accs: [T; N],
+    comps: [T::Comp; N],
     cnts: [C; N],
+    outlier_threshold: Option<u64>,
+    rejected: u64,
+    precision: u8,
+    dirty: (usize, usize),
+    mins: [T; N],
+    maxs: [T; N],
+    show_range: bool,
+    labels: Option<&'static [&'static str; N]>,
 }
 
 //ip Default for AccArray
-impl<const S: bool, T, C, const N: usize> std::default::Default for AccArray<S, T, C, N>
+impl<const S: bool, T, C, const N: usize, A> core::default::Default for AccArray<S, T, C, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
     C: TraceCount,
-    [T; N]: Default,
-    [C; N]: Default,
 {
     fn default() -> Self {
-        let base = BaseTimer::default();
-        let accs = <[T; N]>::default();
-        let cnts = <[C; N]>::default();
-        Self { base, accs, cnts }
+        let accs = [T::default(); N];
+        let cnts = [C::default(); N];
+        let mut s = Self::with_values(accs, cnts);
+        s.dirty = (N, 0);
+        s
+    }
+}
+
+//ip AccArray<true>
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+))]
+#[allow(private_bounds)]
+impl<T, C, const N: usize> AccArray<true, T, C, N>
+where
+    T: TraceValue,
+    C: TraceCount + crate::private::ConstZero,
+{
+    //cp new
+    /// Create a new AccArray in a const context, such as for a
+    /// `static`, with zero values and counts
+    ///
+    /// This is only available on architectures with an assembler
+    /// timer, and for the count types of the crate (as a count type
+    /// outside the crate has no known zero in a const context); the
+    /// start is 0 until the first `start`
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// static COUNTERS: AccArray<true, u64, u32, 16> = AccArray::new();
+    /// assert_eq!(COUNTERS.cnts(), &[0; 16]);
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            base: BaseTimer::new(),
+            accs: [T::ZERO; N],
+            comps: [T::COMP_ZERO; N],
+            cnts: [C::ZERO; N],
+            outlier_threshold: None,
+            rejected: 0,
+            precision: 0,
+            dirty: (N, 0),
+            mins: [T::MAX; N],
+            maxs: [T::ZERO; N],
+            show_range: false,
+            labels: None,
+        }
     }
 }
 
 //ip Display for AccArray
-impl<const S: bool, T, C, const N: usize> std::fmt::Display for AccArray<S, T, C, N>
+impl<const S: bool, T, C, const N: usize, A> core::fmt::Display for AccArray<S, T, C, N, A>
 where
-    TDesc<S>: TArch,
-    T: TraceValue + std::fmt::Display + std::ops::Div<C>,
-    <T as std::ops::Div<C>>::Output: std::fmt::Display,
-    C: TraceCount + std::fmt::Display + PartialEq<C>,
-    [T; N]: Default,
-    [C; N]: Default,
+    A: TArch,
+    T: TraceValue + core::fmt::Display + core::ops::Div<C>,
+    <T as core::ops::Div<C>>::Output: core::fmt::Display,
+    C: TraceCount + core::fmt::Display + PartialEq<C>,
 {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        let def_c: C = C::default();
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         write! {fmt, "["}?;
         for i in 0..N {
             if i != 0 {
                 write! {fmt, ", "}?;
             }
-            if self.cnts[i] == def_c {
-                write!(fmt, "({}, {}, -)", self.accs[i], self.cnts[i])?;
-            } else {
-                write!(
-                    fmt,
-                    "({}, {}, {})",
-                    self.accs[i],
-                    self.cnts[i],
-                    self.accs[i] / self.cnts[i]
-                )?;
-            }
+            let range = self.show_range.then(|| (self.mins[i], self.maxs[i]));
+            fmt_acc_cnt(fmt, self.accs[i], self.cnts[i], self.precision, range)?;
         }
         write! {fmt, "]"}
     }
 }
 
 //ip AccArray
-impl<const S: bool, T, C, const N: usize> AccArray<S, T, C, N>
+impl<const S: bool, T, C, const N: usize, A> AccArray<S, T, C, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
     C: TraceCount,
 {
+    //cp with_values
+    /// Create a new AccArray with the accumulated values and counts
+    /// preloaded, and a fresh timer
+    ///
+    /// This can be used to continue accumulating from a previously
+    /// persisted snapshot (such as from before a restart)
+    pub fn with_values(accs: [T; N], cnts: [C; N]) -> Self {
+        let base = BaseTimer::default();
+        Self {
+            base,
+            accs,
+            comps: [T::Comp::default(); N],
+            cnts,
+            outlier_threshold: None,
+            rejected: 0,
+            precision: 0,
+            dirty: (0, N),
+            mins: [T::MAX; N],
+            maxs: [T::default(); N],
+            show_range: false,
+            labels: None,
+        }
+    }
+
     //mp clear
-    /// Clear the timer and accumulated values
+    /// Clear the timer, accumulated values and rejected count, keeping
+    /// any outlier threshold
+    ///
+    /// This zeros *all* N entries; for a large AccArray of which only
+    /// a few entries are used, [AccArray::clear_incremental] is
+    /// cheaper (which may matter if it is cleared within a timed
+    /// region)
     pub fn clear(&mut self) {
-        unsafe { *self = std::mem::zeroed() };
+        self.base = BaseTimer::default();
+        self.accs = [T::default(); N];
+        self.comps = [T::Comp::default(); N];
+        self.cnts = [C::default(); N];
+        self.mins = [T::MAX; N];
+        self.maxs = [T::default(); N];
+        self.rejected = 0;
+        self.dirty = (N, 0);
+    }
+
+    //mp clear_incremental
+    /// Clear the timer, accumulated values and rejected count, keeping
+    /// any outlier threshold, as for `clear`
+    ///
+    /// This zeros only the range of entries that have been accumulated
+    /// since the last clear, rather than all N entries
+    pub fn clear_incremental(&mut self) {
+        let (lo, hi) = self.dirty;
+        if lo < hi {
+            self.accs[lo..hi].fill(T::default());
+            self.comps[lo..hi].fill(T::Comp::default());
+            self.cnts[lo..hi].fill(C::default());
+            self.mins[lo..hi].fill(T::MAX);
+            self.maxs[lo..hi].fill(T::default());
+        }
+        self.base = BaseTimer::default();
+        self.rejected = 0;
+        self.dirty = (N, 0);
+    }
+
+    //mp take
+    /// Return the current state, and reset the accumulated values,
+    /// counts and rejected count to zero
+    ///
+    /// The timer (and any outlier threshold) is kept, so a region that
+    /// has been started is still accumulated when it completes. With
+    /// the AccArray inside a `Mutex` this reads and resets in one
+    /// operation, so that no samples are lost between a read and a
+    /// separate `clear`.
+    pub fn take(&mut self) -> Self {
+        let taken = *self;
+        self.accs = [T::default(); N];
+        self.comps = [T::Comp::default(); N];
+        self.cnts = [C::default(); N];
+        self.mins = [T::MAX; N];
+        self.maxs = [T::default(); N];
+        self.rejected = 0;
+        self.dirty = (N, 0);
+        taken
+    }
+
+    //mp snapshot_and_clear
+    /// Return the accumulated values and counts, and reset them (and
+    /// the rejected count) to zero, as for [AccArray::take]
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let mut a = AccArray::<true, u64, u32, 2>::with_values([10, 20], [1, 2]);
+    /// assert_eq!(a.snapshot_and_clear(), ([10, 20], [1, 2]));
+    /// assert_eq!(a.accs(), &[0, 0]);
+    /// ```
+    pub fn snapshot_and_clear(&mut self) -> ([T; N], [C; N]) {
+        let taken = self.take();
+        (taken.accs, taken.cnts)
+    }
+
+    //mp merge
+    /// Add the accumulated values, counts and rejected count of another
+    /// AccArray to this one (saturating), combining the minimum and
+    /// maximum deltas
+    ///
+    /// The timer of the AccArray is not affected; if this has no
+    /// labels then those of *other* are used. Float values are added
+    /// with their compensation terms, so that merged accumulations
+    /// remain compensated.
+    pub fn merge(&mut self, other: &Self) {
+        for i in 0..N {
+            self.accs[i] =
+                self.accs[i].sat_add_value_comp(other.accs[i], other.comps[i], &mut self.comps[i]);
+            self.cnts[i] = self.cnts[i].sat_add_count(other.cnts[i]);
+            if other.mins[i] < self.mins[i] {
+                self.mins[i] = other.mins[i];
+            }
+            if other.maxs[i] > self.maxs[i] {
+                self.maxs[i] = other.maxs[i];
+            }
+        }
+        self.rejected = self.rejected.saturating_add(other.rejected);
+        self.dirty.0 = self.dirty.0.min(other.dirty.0);
+        self.dirty.1 = self.dirty.1.max(other.dirty.1);
+        if self.labels.is_none() {
+            self.labels = other.labels;
+        }
+    }
+
+    //cp diff
+    /// Return the activity between an *earlier* snapshot of this
+    /// AccArray (such as a copy of it) and this one, as the per-index
+    /// difference of the accumulated values, counts and rejected count
+    ///
+    /// The differences saturate at zero (so an index cleared between
+    /// the snapshots shows no activity). The minimum and maximum
+    /// deltas of the interval are not known, and are reset (as are the
+    /// compensation terms of float values); the labels
+    /// and precision are those of this AccArray, and the timer is
+    /// fresh.
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let mut a = AccArray::<true, u64, u32, 2>::default();
+    /// a.acc_n_span(0, 0, 10);
+    /// let t1 = a;
+    /// a.acc_n_span(0, 0, 5);
+    /// a.acc_n_span(1, 0, 7);
+    /// let d = a.diff(&t1);
+    /// assert_eq!(d.accs(), &[5, 7]);
+    /// assert_eq!(d.cnts(), &[1, 1]);
+    /// ```
+    pub fn diff(&self, earlier: &Self) -> Self {
+        let accs = core::array::from_fn(|i| self.accs[i].sat_sub_value(earlier.accs[i]));
+        let cnts = core::array::from_fn(|i| self.cnts[i].sat_sub_count(earlier.cnts[i]));
+        let mut diff = Self::with_values(accs, cnts);
+        diff.rejected = self.rejected.saturating_sub(earlier.rejected);
+        diff.precision = self.precision;
+        diff.labels = self.labels;
+        diff
+    }
+
+    //cp merge_from_iter
+    /// Create a new AccArray by merging all of the AccArrays from an
+    /// iterator, such as one for each worker thread
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let workers: Vec<_> = (1..=3)
+    ///     .map(|n| AccArray::<true, u64, u32, 2>::with_values([10 * n, 0], [n as u32, 0]))
+    ///     .collect();
+    /// let total = AccArray::merge_from_iter(workers);
+    /// assert_eq!(total.accs(), &[60, 0]);
+    /// assert_eq!(total.cnts(), &[6, 0]);
+    /// ```
+    pub fn merge_from_iter<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+        let mut merged = Self::default();
+        for a in iter {
+            merged.merge(&a);
+        }
+        merged
+    }
+
+    //mp scale
+    /// Multiply the accumulated values (and the minimum and maximum
+    /// deltas) by a factor, such as the reciprocal of
+    /// [crate::Calibration::ticks_per_nanosecond] to convert ticks to
+    /// nanoseconds
+    ///
+    /// For an integer accumulator type each value is converted to an
+    /// f64, scaled, and rounded to the nearest integer, saturating at
+    /// zero and at the maximum of the type; an f64 has 53 bits of
+    /// mantissa, so accumulated values above 2^53 lose their least
+    /// significant bits in the conversion. The counts are unchanged.
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let mut a = AccArray::<true, u32, u32, 3>::with_values([10, 15, u32::MAX], [1, 2, 3]);
+    /// a.scale(2.5);
+    /// assert_eq!(a.accs(), &[25, 38, u32::MAX]);
+    /// assert_eq!(a.cnts(), &[1, 2, 3]);
+    /// ```
+    pub fn scale(&mut self, factor: f64) {
+        for i in 0..N {
+            self.accs[i] = self.accs[i].scale(factor);
+            if self.mins[i] != T::MAX {
+                self.mins[i] = self.mins[i].scale(factor);
+            }
+            self.maxs[i] = self.maxs[i].scale(factor);
+        }
+        self.comps = [T::Comp::default(); N];
+    }
+
+    //cp scaled
+    /// Return a copy of the AccArray with its accumulated values
+    /// multiplied by a factor, as for [AccArray::scale]
+    ///
+    /// This is most useful with an accumulator type of f32 or f64,
+    /// for which there is no rounding
+    pub fn scaled(&self, factor: f64) -> Self {
+        let mut s = *self;
+        s.scale(factor);
+        s
+    }
+
+    //mp set_display_precision
+    /// Set the number of decimal places used for the averages when
+    /// displayed; with the default of 0 the average is the accumulated
+    /// value divided by the count, i.e. integer-truncated for integer
+    /// types
+    pub fn set_display_precision(&mut self, digits: u8) {
+        self.precision = digits;
+    }
+
+    //mp set_display_range
+    /// Set whether the minimum and maximum deltas of each index are
+    /// displayed after the average, as `min..max`
+    pub fn set_display_range(&mut self, show_range: bool) {
+        self.show_range = show_range;
+    }
+
+    //mp set_labels
+    /// Set the labels of the indices, for reports and metrics
+    ///
+    /// Labels are kept on a clear
+    pub fn set_labels(&mut self, labels: &'static [&'static str; N]) {
+        self.labels = Some(labels);
+    }
+
+    //ap label
+    /// Return the label of an index, if labels have been set
+    pub fn label(&self, index: usize) -> Option<&'static str> {
+        self.labels.and_then(|l| l.get(index).copied())
+    }
+
+    //mp set_outlier_threshold
+    /// Set the outlier threshold; deltas larger than this are then
+    /// neither accumulated nor counted
+    pub fn set_outlier_threshold(&mut self, ticks: u64) {
+        self.outlier_threshold = Some(ticks);
+    }
+
+    //ap rejected_count
+    /// Return the number of deltas rejected as outliers since the last
+    /// clear
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected
+    }
+
+    //mi is_outlier
+    /// Return true if a delta exceeds the outlier threshold, counting
+    /// it as rejected
+    #[inline(always)]
+    fn is_outlier(&mut self, delta: u64) -> bool {
+        if self.outlier_threshold.is_some_and(|t| delta > t) {
+            self.rejected = self.rejected.saturating_add(1);
+            true
+        } else {
+            false
+        }
+    }
+
+    //mi touch
+    /// Record that an entry has been accumulated with a delta, for
+    /// `clear_incremental` and the minimum and maximum of the entry
+    #[inline(always)]
+    fn touch(&mut self, index: usize, delta: u64) {
+        self.dirty.0 = self.dirty.0.min(index);
+        self.dirty.1 = self.dirty.1.max(index + 1);
+        let delta = T::default().sat_add(delta);
+        if delta < self.mins[index] {
+            self.mins[index] = delta;
+        }
+        if delta > self.maxs[index] {
+            self.maxs[index] = delta;
+        }
     }
 
     //mp start
@@ -115,8 +608,12 @@ where
     pub fn acc_n(&mut self, index: usize) {
         if index < N {
             let delta: u64 = self.base.elapsed();
-            self.accs[index] = self.accs[index].sat_add(delta);
+            if self.is_outlier(delta) {
+                return;
+            }
+            self.accs[index] = self.accs[index].sat_add_comp(delta, &mut self.comps[index]);
             self.cnts[index].sat_inc();
+            self.touch(index, delta);
         }
     }
 
@@ -126,11 +623,132 @@ where
     pub fn acc_n_restart(&mut self, index: usize) {
         if index < N {
             let delta = self.base.elapsed_and_update();
-            self.accs[index] = self.accs[index].sat_add(delta);
+            if self.is_outlier(delta) {
+                return;
+            }
+            self.accs[index] = self.accs[index].sat_add_comp(delta, &mut self.comps[index]);
+            self.cnts[index].sat_inc();
+            self.touch(index, delta);
+        }
+    }
+
+    //mp acc_n_checked
+    /// Add the ticks on exit to a specific region, returning true if
+    /// both the value and the count were accumulated without
+    /// saturating
+    ///
+    /// This returns false if either has saturated (so data is being
+    /// lost, and the accumulator should perhaps be flushed and
+    /// cleared), or if the index is out of range; a delta rejected as
+    /// an outlier is not data loss, and so this returns true
+    #[inline(always)]
+    pub fn acc_n_checked(&mut self, index: usize) -> bool {
+        if index < N {
+            let delta: u64 = self.base.elapsed();
+            if self.is_outlier(delta) {
+                return true;
+            }
+            let (acc, acc_ok) =
+                self.accs[index].sat_add_comp_checked(delta, &mut self.comps[index]);
+            self.accs[index] = acc;
+            let cnt_ok = self.cnts[index].sat_inc_checked();
+            self.touch(index, delta);
+            acc_ok && cnt_ok
+        } else {
+            false
+        }
+    }
+
+    //mp acc_n_span
+    /// Add the ticks between two tick values (such as those returned
+    /// by [crate::anchor]) to a specific region, without using the
+    /// timer of the AccArray
+    ///
+    /// The delta wraps (as for the timers) if *stop_tick* is before
+    /// *start_tick*; it is subject to any outlier threshold
+    #[inline(always)]
+    pub fn acc_n_span(&mut self, index: usize, start_tick: u64, stop_tick: u64) {
+        self.acc_delta(index, stop_tick.wrapping_sub(start_tick));
+    }
+
+    //mi acc_delta
+    /// Add a delta to a specific region, subject to any outlier
+    /// threshold
+    #[inline(always)]
+    fn acc_delta(&mut self, index: usize, delta: u64) {
+        if index < N {
+            if self.is_outlier(delta) {
+                return;
+            }
+            self.accs[index] = self.accs[index].sat_add_comp(delta, &mut self.comps[index]);
             self.cnts[index].sat_inc();
+            self.touch(index, delta);
         }
     }
 
+    //mp acc_n_if_over
+    /// Add the ticks on exit to a specific region only if they exceed
+    /// *threshold*
+    ///
+    /// This permits one index to accumulate the total time for a
+    /// region (with [AccArray::acc_n]) and another to accumulate just
+    /// the occurrences that were over budget
+    #[inline(always)]
+    pub fn acc_n_if_over(&mut self, index: usize, threshold: u64) {
+        if index < N {
+            let delta: u64 = self.base.elapsed();
+            if delta > threshold && !self.is_outlier(delta) {
+                self.accs[index] = self.accs[index].sat_add_comp(delta, &mut self.comps[index]);
+                self.cnts[index].sat_inc();
+                self.touch(index, delta);
+            }
+        }
+    }
+
+    //mp time_result
+    /// Time a fallible operation, accumulating the time taken in
+    /// *ok_index* if it succeeds and in *err_index* if it fails, and
+    /// return its result
+    ///
+    /// This starts the underlying timer
+    pub fn time_result<R, E, F: FnOnce() -> Result<R, E>>(
+        &mut self,
+        ok_index: usize,
+        err_index: usize,
+        f: F,
+    ) -> Result<R, E> {
+        self.start();
+        let result = f();
+        self.acc_n(if result.is_ok() { ok_index } else { err_index });
+        result
+    }
+
+    //mp acc_by
+    /// Time an operation that determines the index to accumulate into,
+    /// such as a dispatch on an opcode that it decodes, and return its
+    /// result
+    ///
+    /// This starts the underlying timer, invokes *f*, and accumulates
+    /// the time taken into the index that *f* returns
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let mut arr = AccArray::<true, u64, u32, 4>::default();
+    /// let opcode = 2_u8;
+    /// let r = arr.acc_by(|| {
+    ///     // decode and execute!
+    ///     (opcode as usize, opcode + 1)
+    /// });
+    /// assert_eq!(r, 3);
+    /// assert_eq!(arr.cnts(), &[0, 0, 1, 0]);
+    /// ```
+    pub fn acc_by<R, F: FnOnce() -> (usize, R)>(&mut self, f: F) -> R {
+        self.start();
+        let (index, result) = f();
+        self.acc_n(index);
+        result
+    }
+
     //mp accs
     /// Return the accumulated values
     pub fn accs(&self) -> &[T; N] {
@@ -142,6 +760,410 @@ where
     pub fn cnts(&self) -> &[C; N] {
         &self.cnts
     }
+
+    //ap is_empty
+    /// Return true if nothing has been accumulated since the AccArray
+    /// was created or cleared, i.e. every count is zero
+    ///
+    /// As this is based on the counts it is always true for a count
+    /// type of ()
+    pub fn is_empty(&self) -> bool {
+        self.cnts.iter().all(|c| c.as_usize() == 0)
+    }
+
+    //ap mins
+    /// Return the smallest delta accumulated into each index
+    ///
+    /// This is the largest value of *T* for an index that has not been
+    /// accumulated
+    pub fn mins(&self) -> &[T; N] {
+        &self.mins
+    }
+
+    //ap maxs
+    /// Return the largest delta accumulated into each index
+    pub fn maxs(&self) -> &[T; N] {
+        &self.maxs
+    }
+
+    //ap iter_stats
+    /// Iterate over the statistics of each entry, including the average
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let a = AccArray::<true, u64, u64, 3>::with_values([10, 0, 90], [2, 0, 3]);
+    /// let hottest = a.iter_stats().max_by_key(|s| s.avg).unwrap();
+    /// assert_eq!(hottest.index, 2);
+    /// assert_eq!(hottest.avg, Some(30));
+    /// ```
+    pub fn iter_stats(&self) -> impl Iterator<Item = AccStat<T, C>> + '_
+    where
+        T: core::ops::Div<C>,
+        C: PartialEq<C>,
+    {
+        (0..N).map(|i| AccStat::new(i, self.accs[i], self.cnts[i]))
+    }
+
+    //ap percentages
+    /// Return the accumulated value of each index as a percentage of
+    /// the total accumulated across all the indices
+    ///
+    /// This does not overflow for large accumulated values; if nothing
+    /// has been accumulated then every percentage is 0
+    pub fn percentages(&self) -> [f64; N] {
+        let total: f64 = self.accs.iter().map(|a| a.as_f64()).sum();
+        self.accs.map(|a| percentage(a.as_f64(), total))
+    }
+
+    //ap export
+    /// Return a [TimingReport] of the accumulated value and count of
+    /// each index, labelled with the labels if set (and otherwise the
+    /// index)
+    #[cfg(feature = "std")]
+    pub fn export(&self) -> TimingReport {
+        let mut report = TimingReport::default();
+        for (i, (a, c)) in self.accs.iter().zip(self.cnts.iter()).enumerate() {
+            let label = self.label(i).map_or_else(|| i.to_string(), str::to_string);
+            let a: Delta = (*a).into();
+            report.push(label, a.into(), c.as_usize() as u64);
+        }
+        report
+    }
+
+    //mp emit_metrics
+    /// Export the accumulated value and count of each index to the
+    /// `metrics` crate, as absolute counters named
+    /// `<prefix>.<label>.ticks` and `<prefix>.<label>.count`, where
+    /// the label is the index if labels have not been set
+    ///
+    /// This is only available with the `metrics` feature
+    #[cfg(feature = "metrics")]
+    pub fn emit_metrics(&self, prefix: &str) {
+        for (i, (a, c)) in self.accs.iter().zip(self.cnts.iter()).enumerate() {
+            let name = self.label(i).map_or_else(|| i.to_string(), str::to_string);
+            emit_metric(prefix, &name, a.as_f64(), c.as_usize());
+        }
+    }
+
+    //mp write_csv
+    /// Write the accumulated value, count and average of each index as
+    /// CSV, with a header row of `index,sum,count,avg`
+    ///
+    /// If *C* is () then the count and average columns are blank
+    ///
+    /// This is only available with the `csv` feature
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let a = AccArray::<true, u64, u32, 2>::with_values([10, 0], [4, 0]);
+    /// let mut csv = vec![];
+    /// a.write_csv(&mut csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "index,sum,count,avg\n0,10,4,2.5\n1,0,0,\n");
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn write_csv<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        T: core::fmt::Display,
+    {
+        write_csv(w, self.accs.iter().copied().zip(self.cnts.iter().copied()))
+    }
+
+    //mp to_json
+    /// Return a JSON summary of the accumulated value, count and
+    /// average of each index, of the form
+    /// `{"slots":[{"index":0,"sum":123,"count":4,"avg":30.75},...]}`
+    ///
+    /// This is a fixed, human-readable schema for dashboards and
+    /// monitoring tools, rather than a serialization that can be read
+    /// back (for which see the `serde` feature); the average is null
+    /// for a count of 0, and if *C* is () then the count and average
+    /// are both null
+    ///
+    /// This is only available with the `json` feature
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// let a = AccArray::<true, u64, u32, 2>::with_values([10, 0], [4, 0]);
+    /// assert_eq!(
+    ///     a.to_json(),
+    ///     r#"{"slots":[{"index":0,"sum":10,"count":4,"avg":2.5},{"index":1,"sum":0,"count":0,"avg":null}]}"#
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String
+    where
+        T: core::fmt::Display,
+    {
+        to_json(self.accs.iter().copied().zip(self.cnts.iter().copied()))
+    }
+
+    //mp scope
+    /// Return a guard that accumulates the time elapsed from its
+    /// creation to when it is dropped into the specified index
+    ///
+    /// The guard has its own start time, and does not use (or restart)
+    /// the timer of the AccArray, so it measures just its own region
+    /// even if that is nested within one started with 'start'.
+    ///
+    /// The guard holds a mutable borrow of the AccArray, but
+    /// dereferences to it, so the AccArray can be used through the
+    /// guard (including for nested guards) while it is live. The
+    /// accumulation occurs however the scope is exited, including
+    /// early returns and panics (on unwind); it is subject to any
+    /// outlier threshold.
+    ///
+    /// ```
+    /// # use cpu_timer::AccArray;
+    /// const REGION_PARSE: usize = 0;
+    /// const REGION_LEX: usize = 1;
+    /// let mut arr = AccArray::<true, u64, u32, 4>::default();
+    /// {
+    ///     let mut g = arr.scope(REGION_PARSE);
+    ///     {
+    ///         let _g = g.scope(REGION_LEX);
+    ///         // lex something!
+    ///     }
+    ///     // parse something!
+    /// }
+    /// assert_eq!(arr.cnts()[REGION_PARSE], 1);
+    /// assert_eq!(arr.cnts()[REGION_LEX], 1);
+    /// assert!(arr.accs()[REGION_LEX] <= arr.accs()[REGION_PARSE]);
+    /// ```
+    pub fn scope(&mut self, index: usize) -> AccGuard<'_, S, T, C, N, A> {
+        let mut base = BaseTimer::default();
+        base.start();
+        AccGuard {
+            acc: self,
+            index,
+            base,
+        }
+    }
+
+    //mp slot
+    /// Return an [AccSlot] for an index, which is a [RecordDelta] that
+    /// accumulates into that index (subject to any outlier threshold)
+    pub fn slot(&mut self, index: usize) -> AccSlot<'_, S, T, C, N, A> {
+        AccSlot { acc: self, index }
+    }
+}
+
+//ip Startable for AccArray
+impl<const S: bool, T, C, const N: usize, A> Startable<S, A> for AccArray<S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+    }
+}
+
+//ip Serialize for AccArray
+/// Only the accumulated values and counts are serialized; the timer is
+/// a live reading, and meaningless elsewhere
+#[cfg(feature = "serde")]
+impl<const S: bool, T, C, const N: usize, A> serde::Serialize for AccArray<S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue + serde::Serialize,
+    C: TraceCount + serde::Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = serializer.serialize_struct("AccArray", 2)?;
+        st.serialize_field("accs", &self.accs[..])?;
+        st.serialize_field("cnts", &self.cnts[..])?;
+        st.end()
+    }
+}
+
+//ip Deserialize for AccArray
+/// The accumulated values and counts must each have *N* entries; the
+/// AccArray is created as for [AccArray::with_values]
+#[cfg(feature = "serde")]
+impl<'de, const S: bool, T, C, const N: usize, A> serde::Deserialize<'de>
+    for AccArray<S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue + serde::Deserialize<'de>,
+    C: TraceCount + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "AccArray")]
+        struct Data<T, C> {
+            accs: Vec<T>,
+            cnts: Vec<C>,
+        }
+        let data = Data::<T, C>::deserialize(deserializer)?;
+        let n = (data.accs.len(), data.cnts.len());
+        let expected = format!("{N} entries");
+        let accs = <[T; N]>::try_from(data.accs)
+            .map_err(|_| D::Error::invalid_length(n.0, &expected.as_str()))?;
+        let cnts = <[C; N]>::try_from(data.cnts)
+            .map_err(|_| D::Error::invalid_length(n.1, &expected.as_str()))?;
+        Ok(Self::with_values(accs, cnts))
+    }
+}
+
+//a AccArray rates
+//fi per_second
+/// Return the per-second rate of the increase from *old* to *new* of
+/// each of a pair of arrays of values, clamped at zero
+fn per_second<const N: usize>(new: [f64; N], old: [f64; N], elapsed_secs: f64) -> [f64; N] {
+    let mut rates = [0.0; N];
+    if elapsed_secs > 0.0 {
+        for (r, (n, o)) in rates.iter_mut().zip(new.iter().zip(old.iter())) {
+            *r = (n - o).max(0.0) / elapsed_secs;
+        }
+    }
+    rates
+}
+
+//fp rate
+/// Return the number of occurrences per second of each index of an
+/// [AccArray], given two copies (snapshots) of it taken *elapsed_secs*
+/// apart
+///
+/// An index whose count has decreased (such as if the AccArray was
+/// cleared between the snapshots) has a rate of zero, as does every
+/// index if *elapsed_secs* is not positive
+///
+/// ```
+/// # use cpu_timer::{rate, AccArray};
+/// let old = AccArray::<true, u64, u32, 2>::with_values([0, 0], [10, 0]);
+/// let new = AccArray::<true, u64, u32, 2>::with_values([0, 0], [30, 5]);
+/// assert_eq!(rate(&new, &old, 2.0), [10.0, 2.5]);
+/// ```
+pub fn rate<const S: bool, T, C, const N: usize>(
+    new: &AccArray<S, T, C, N>,
+    old: &AccArray<S, T, C, N>,
+    elapsed_secs: f64,
+) -> [f64; N]
+where
+    TDesc<S>: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    per_second(
+        new.cnts.map(|c| c.as_f64()),
+        old.cnts.map(|c| c.as_f64()),
+        elapsed_secs,
+    )
+}
+
+//fp tick_rate
+/// Return the number of ticks accumulated per second by each index of
+/// an [AccArray], given two copies (snapshots) of it taken
+/// *elapsed_secs* apart; divided by the tick frequency, this is the
+/// fraction of the time for which each region is busy
+///
+/// As with [rate], decreases are clamped to zero
+pub fn tick_rate<const S: bool, T, C, const N: usize>(
+    new: &AccArray<S, T, C, N>,
+    old: &AccArray<S, T, C, N>,
+    elapsed_secs: f64,
+) -> [f64; N]
+where
+    TDesc<S>: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    per_second(
+        new.accs.map(|a| a.as_f64()),
+        old.accs.map(|a| a.as_f64()),
+        elapsed_secs,
+    )
+}
+
+//a AccGuard
+//tp AccGuard
+/// A guard returned by [AccArray::scope], which accumulates the time
+/// elapsed since it was created into an index of the [AccArray] when
+/// it is dropped
+///
+/// The guard has its own start time; it dereferences to the
+/// [AccArray], so that this can be used (such as for nested guards)
+/// while the guard is live
+pub struct AccGuard<
+    'a,
+    const S: bool,
+    T: TraceValue,
+    C: TraceCount,
+    const N: usize,
+    A: TArch = TDesc<S>,
+> {
+    acc: &'a mut AccArray<S, T, C, N, A>,
+    index: usize,
+    base: BaseTimer<S, A>,
+}
+
+//ip Deref for AccGuard
+impl<const S: bool, T, C, const N: usize, A> core::ops::Deref for AccGuard<'_, S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    type Target = AccArray<S, T, C, N, A>;
+    fn deref(&self) -> &Self::Target {
+        self.acc
+    }
+}
+
+//ip DerefMut for AccGuard
+impl<const S: bool, T, C, const N: usize, A> core::ops::DerefMut for AccGuard<'_, S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.acc
+    }
+}
+
+//ip Drop for AccGuard
+impl<const S: bool, T, C, const N: usize, A> core::ops::Drop for AccGuard<'_, S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn drop(&mut self) {
+        let delta = self.base.elapsed();
+        self.acc.acc_delta(self.index, delta);
+    }
+}
+
+//a AccSlot
+//tp AccSlot
+/// A [RecordDelta] returned by [AccArray::slot], which accumulates
+/// recorded deltas into one index of the [AccArray]
+pub struct AccSlot<
+    'a,
+    const S: bool,
+    T: TraceValue,
+    C: TraceCount,
+    const N: usize,
+    A: TArch = TDesc<S>,
+> {
+    acc: &'a mut AccArray<S, T, C, N, A>,
+    index: usize,
+}
+
+//ip RecordDelta for AccSlot
+impl<const S: bool, T, C, const N: usize, A> RecordDelta<S> for AccSlot<'_, S, T, C, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn record(&mut self, delta: u64) {
+        self.acc.acc_delta(self.index, delta);
+    }
 }
 
 //a AccVec
@@ -153,7 +1175,9 @@ where
 ///
 /// The AccVec is generic on whether to use the CPU-specific
 /// architcture timer implementation, the value to accumulate times in
-/// (e.g. u64), the value to use to count occurrences (e.g. u32).
+/// (e.g. u64), the value to use to count occurrences (e.g. u32). As
+/// for an AccArray the accumulated values and counts saturate rather
+/// than wrapping, and float accumulators are compensated.
 ///
 /// An AccVec can be created with a specific capacity - and memory is
 /// allocated at this time for that capacity; when it is cleared, the
@@ -268,20 +1292,25 @@ where
 ///     println!("Counting {i} characters took an average of {} ticks", avg);
 /// }
 /// ```
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
-pub struct AccVec<const S: bool, T: TraceValue, C: TraceCount>
-where
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct AccVec<const S: bool, T: TraceValue, C: TraceCount, A: TArch = TDesc<S>> {
+    base: BaseTimer<S, A>,
     index: usize,
     acc_cnts: Vec<(T, C)>,
+    comps: Vec<T::Comp>,
+    labels: Vec<&'static str>,
+    samples: Option<Vec<Vec<u64>>>,
+    precision: u8,
+    #[cfg(feature = "sequence")]
+    sequence: Vec<usize>,
 }
 
 //ip Default for AccVec
-impl<const S: bool, T, C> std::default::Default for AccVec<S, T, C>
+#[cfg(feature = "std")]
+impl<const S: bool, T, C, A> core::default::Default for AccVec<S, T, C, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
     C: TraceCount,
 {
@@ -293,55 +1322,295 @@ where
             base,
             index,
             acc_cnts,
+            comps: vec![],
+            labels: vec![],
+            samples: None,
+            precision: 0,
+            #[cfg(feature = "sequence")]
+            sequence: vec![],
         }
     }
 }
 
 //ip Display for AccVec
-impl<const S: bool, T, C> std::fmt::Display for AccVec<S, T, C>
+#[cfg(feature = "std")]
+impl<const S: bool, T, C, A> core::fmt::Display for AccVec<S, T, C, A>
 where
-    TDesc<S>: TArch,
-    T: TraceValue + std::fmt::Display + std::ops::Div<C>,
-    <T as std::ops::Div<C>>::Output: std::fmt::Display,
-    C: TraceCount + std::fmt::Display + PartialEq<C>,
+    A: TArch,
+    T: TraceValue + core::fmt::Display + core::ops::Div<C>,
+    <T as core::ops::Div<C>>::Output: core::fmt::Display,
+    C: TraceCount + core::fmt::Display + PartialEq<C>,
 {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        let def_c: C = C::default();
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         write! {fmt, "["}?;
         for (i, ac) in self.acc_cnts.iter().enumerate() {
             if i != 0 {
                 write! {fmt, ", "}?;
             }
-            if ac.1 == def_c {
-                write!(fmt, "({}, {}, -)", ac.0, ac.1)?;
-            } else {
-                write!(fmt, "({}, {}, {})", ac.0, ac.1, ac.0 / ac.1)?;
-            }
+            fmt_acc_cnt(fmt, ac.0, ac.1, self.precision, None)?;
         }
         write! {fmt, "]"}
     }
 }
 
 //ip AccVec
-impl<const S: bool, T, C> AccVec<S, T, C>
+#[cfg(feature = "std")]
+impl<const S: bool, T, C, A> AccVec<S, T, C, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
     C: TraceCount,
 {
     //mp with_capacity
     /// Create a new AccVec of a certain size
+    #[allow(clippy::field_reassign_with_default)]
     pub fn with_capacity(n: usize) -> Self {
         let mut s = Self::default();
         s.acc_cnts = vec![(T::default(), C::default()); n];
         s
     }
 
+    //cp with_samples
+    /// Create a new AccVec of a certain size that also retains every
+    /// individual delta accumulated into each entry, so that
+    /// percentiles can be calculated (with [AccVec::percentiles])
+    ///
+    /// The samples use memory in proportion to the number of deltas
+    /// accumulated, until the AccVec is cleared
+    pub fn with_samples(n: usize) -> Self {
+        Self {
+            samples: Some(vec![vec![]; n]),
+            ..Self::with_capacity(n)
+        }
+    }
+
+    //cp from_acc_cnts
+    /// Create a new AccVec with the given values and counts, as if
+    /// they had all been pushed since the last start
+    pub(crate) fn from_acc_cnts(acc_cnts: Vec<(T, C)>) -> Self {
+        let index = acc_cnts.len();
+        Self {
+            index,
+            acc_cnts,
+            ..Self::default()
+        }
+    }
+
+    //cp merge_all
+    /// Create a new AccVec with the saturating sums of the values and
+    /// counts of each entry of all the given AccVecs, sized to the
+    /// longest of them
+    ///
+    /// This is the 'reduce' step for profiling with an AccVec for each
+    /// worker thread (with the same entry layout), avoiding contention
+    /// while the workers run; any retained samples are not merged
+    pub fn merge_all(vecs: &[Self]) -> Self {
+        let mut merged = Self::default();
+        for v in vecs {
+            merged.merge(v);
+        }
+        merged
+    }
+
+    //mp merge
+    /// Add the accumulated values and counts of each entry of another
+    /// AccVec to this one (saturating), first extending this to the
+    /// length of *other* if it is shorter
+    ///
+    /// The timer of the AccVec is not affected; if *other* has more
+    /// labels than this then its labels are used
+    pub fn merge(&mut self, other: &Self) {
+        if self.acc_cnts.len() < other.acc_cnts.len() {
+            self.acc_cnts
+                .resize(other.acc_cnts.len(), (T::default(), C::default()));
+        }
+        self.comps.resize(self.acc_cnts.len(), T::Comp::default());
+        for (i, (m, ac)) in self
+            .acc_cnts
+            .iter_mut()
+            .zip(other.acc_cnts.iter())
+            .enumerate()
+        {
+            let other_comp = other.comps.get(i).copied().unwrap_or_default();
+            m.0 = m.0.sat_add_value_comp(ac.0, other_comp, &mut self.comps[i]);
+            m.1 = m.1.sat_add_count(ac.1);
+        }
+        self.index = self.index.max(other.index);
+        if other.labels.len() > self.labels.len() {
+            self.labels = other.labels.clone();
+        }
+    }
+
+    //mp reserve
+    /// Reserve capacity for at least *additional* more entries in
+    /// the store, without changing the current entries
+    ///
+    /// This can be used prior to a burst of `acc_push` invocations,
+    /// so that the store is not reallocated during a push (which
+    /// would add the allocator time to the measurement)
+    pub fn reserve(&mut self, additional: usize) {
+        self.acc_cnts.reserve(additional);
+    }
+
+    //mp set_display_precision
+    /// Set the number of decimal places used for the averages when
+    /// displayed; with the default of 0 the average is the accumulated
+    /// value divided by the count, i.e. integer-truncated for integer
+    /// types
+    pub fn set_display_precision(&mut self, digits: u8) {
+        self.precision = digits;
+    }
+
+    //mp set_label
+    /// Set the label of an entry in the store
+    ///
+    /// Labels are kept separately from the entries, and so an entry
+    /// may be labelled before it has been pushed; labels are kept on a
+    /// clear
+    pub fn set_label(&mut self, index: usize, label: &'static str) {
+        if self.labels.len() <= index {
+            self.labels.resize(index + 1, "");
+        }
+        self.labels[index] = label;
+    }
+
+    //ap label
+    /// Return the label of an entry in the store, if it has one
+    pub fn label(&self, index: usize) -> Option<&'static str> {
+        self.labels.get(index).copied().filter(|l| !l.is_empty())
+    }
+
+    //ap worst_regression
+    /// Return the index of the entry whose average has increased the
+    /// most relative to a baseline, and that relative increase (0.5
+    /// for an average 50% larger)
+    ///
+    /// Each labelled entry is compared with the entry of the baseline
+    /// with the same label; an entry without a label is compared with
+    /// the baseline entry with the same index. Entries with no
+    /// counterpart in the baseline, or where either count (or the
+    /// baseline average) is zero, are ignored. This is None if no
+    /// entry's average has increased.
+    ///
+    /// ```
+    /// # use cpu_timer::AccVec;
+    /// let mut baseline = AccVec::<true, u64, u32>::default();
+    /// baseline.record(100);
+    /// baseline.record(100);
+    /// let mut current = AccVec::<true, u64, u32>::default();
+    /// current.record(110);
+    /// current.record(150);
+    /// assert_eq!(current.worst_regression(&baseline), Some((1, 0.5)));
+    /// ```
+    pub fn worst_regression(&self, baseline: &Self) -> Option<(usize, f64)> {
+        let avg = |(a, c): (T, C)| {
+            let c = c.as_f64();
+            (c != 0.0).then(|| a.as_f64() / c)
+        };
+        let mut worst = None;
+        for (i, ac) in self.acc_cnts.iter().enumerate() {
+            let j = match self.label(i) {
+                Some(l) => (0..baseline.acc_cnts.len()).find(|j| baseline.label(*j) == Some(l)),
+                None => Some(i),
+            };
+            let Some(base) = j.and_then(|j| baseline.acc_cnts.get(j)) else {
+                continue;
+            };
+            let (Some(cur), Some(base)) = (avg(*ac), avg(*base)) else {
+                continue;
+            };
+            if base == 0.0 {
+                continue;
+            }
+            let increase = cur / base - 1.0;
+            if increase > 0.0 && worst.is_none_or(|(_, w)| increase > w) {
+                worst = Some((i, increase));
+            }
+        }
+        worst
+    }
+
+    //ap sum_where
+    /// Return the saturating sums of the values and counts of *every*
+    /// labelled entry whose label satisfies a predicate
+    ///
+    /// Entries without a label are never included
+    ///
+    /// ```
+    /// # use cpu_timer::AccVec;
+    /// let mut t = AccVec::<true, u64, u32>::with_capacity(3);
+    /// t.set_label(0, "db.read");
+    /// t.set_label(1, "db.write");
+    /// t.set_label(2, "render");
+    /// // accumulate!
+    /// let (db_ticks, db_count) = t.sum_where(|l| l.starts_with("db."));
+    /// assert_eq!(db_count, 0);
+    /// ```
+    pub fn sum_where<F: Fn(&str) -> bool>(&self, pred: F) -> (u64, u64) {
+        let mut sum = (0_u64, 0_u64);
+        for (i, (a, c)) in self.acc_cnts.iter().enumerate() {
+            if self.label(i).is_some_and(&pred) {
+                let a: Delta = (*a).into();
+                sum.0 = sum.0.saturating_add(a.into());
+                sum.1 = sum.1.saturating_add(c.as_usize() as u64);
+            }
+        }
+        sum
+    }
+
+    //mp take
+    /// Return the accumulated values and counts up to the last pushed
+    /// (as for [AccVec::acc_cnts]), resetting those entries to zero
+    /// and the last pushed to the start, ready for the next use (such
+    /// as the next frame)
+    ///
+    /// The returned `Vec` is the store of the AccVec, so those entries
+    /// are not copied; the AccVec has a new store with the same
+    /// capacity allocated. Any entries beyond the last pushed (such as
+    /// those accumulated with `acc_n` after a start) are kept in the
+    /// new store, with their samples, as they are not returned.
+    ///
+    /// ```
+    /// # use cpu_timer::AccVec;
+    /// let mut t = AccVec::<true, u64, u32>::default();
+    /// t.record(10);
+    /// t.record(20);
+    /// assert_eq!(t.take(), vec![(10, 1), (20, 1)]);
+    /// assert_eq!(t.acc_cnts(), &[]);
+    /// assert_eq!(t.all_acc_cnts(), &[(0, 0), (0, 0)]);
+    /// ```
+    pub fn take(&mut self) -> Vec<(T, C)> {
+        let n = self.index.min(self.acc_cnts.len());
+        let mut rest = self.acc_cnts.split_off(n);
+        let capacity = self.acc_cnts.capacity();
+        let taken = core::mem::replace(&mut self.acc_cnts, Vec::with_capacity(capacity));
+        self.acc_cnts.resize(n, (T::default(), C::default()));
+        self.acc_cnts.append(&mut rest);
+        for c in self.comps.iter_mut().take(n) {
+            *c = T::Comp::default();
+        }
+        if let Some(samples) = &mut self.samples {
+            for s in samples.iter_mut().take(n) {
+                s.clear();
+            }
+        }
+        self.index = 0;
+        #[cfg(feature = "sequence")]
+        self.sequence.clear();
+        taken
+    }
+
     //mp clear
     /// Clear the timer and accumulated values
     pub fn clear(&mut self) {
         self.index = 0;
         self.acc_cnts.clear();
+        self.comps.clear();
+        if let Some(samples) = &mut self.samples {
+            samples.clear();
+        }
+        #[cfg(feature = "sequence")]
+        self.sequence.clear();
     }
 
     //mp start
@@ -350,6 +1619,56 @@ where
     pub fn start(&mut self) {
         self.base.start();
         self.index = 0;
+        #[cfg(feature = "sequence")]
+        self.sequence.clear();
+    }
+
+    //mi record_index
+    /// Record that an index has been accumulated, if the sequence is
+    /// being recorded
+    #[inline(always)]
+    fn record_index(&mut self, _index: usize) {
+        #[cfg(feature = "sequence")]
+        self.sequence.push(_index);
+    }
+
+    //mi acc_delta
+    /// Accumulate a delta into an entry of the store, which must
+    /// exist, with compensation for a float accumulator
+    ///
+    /// The compensation terms are only extended as entries are
+    /// accumulated into; any beyond their end are zero
+    #[inline(always)]
+    fn acc_delta(&mut self, index: usize, delta: u64) {
+        if self.comps.len() <= index {
+            self.comps.resize(index + 1, T::Comp::default());
+        }
+        let ac = &mut self.acc_cnts[index];
+        ac.0 = ac.0.sat_add_comp(delta, &mut self.comps[index]);
+        ac.1.sat_inc();
+    }
+
+    //mi record_sample
+    /// Record a delta accumulated into an entry, if samples are being
+    /// retained
+    #[inline(always)]
+    fn record_sample(&mut self, index: usize, delta: u64) {
+        if let Some(samples) = &mut self.samples {
+            if samples.len() <= index {
+                samples.resize_with(index + 1, Vec::new);
+            }
+            samples[index].push(delta);
+        }
+    }
+
+    //ap last_sequence
+    /// Return the sequence of indices accumulated (by `acc_n`,
+    /// `acc_push` and their restart variants) since the last start
+    ///
+    /// This is only available with the `sequence` feature
+    #[cfg(feature = "sequence")]
+    pub fn last_sequence(&self) -> &[usize] {
+        &self.sequence
     }
 
     //mp acc_n
@@ -360,10 +1679,11 @@ where
     /// does nothing
     #[inline(always)]
     pub fn acc_n(&mut self, index: usize) {
-        if let Some(ac) = self.acc_cnts.get_mut(index) {
+        if index < self.acc_cnts.len() {
             let delta: u64 = self.base.elapsed();
-            ac.0 = ac.0.sat_add(delta);
-            ac.1.sat_inc();
+            self.acc_delta(index, delta);
+            self.record_index(index);
+            self.record_sample(index, delta);
         }
     }
 
@@ -375,10 +1695,11 @@ where
     /// just restarts the timer
     #[inline(always)]
     pub fn acc_n_restart(&mut self, index: usize) {
-        if let Some(ac) = self.acc_cnts.get_mut(index) {
+        if index < self.acc_cnts.len() {
             let delta = self.base.elapsed_and_update();
-            ac.0 = ac.0.sat_add(delta);
-            ac.1.sat_inc();
+            self.acc_delta(index, delta);
+            self.record_index(index);
+            self.record_sample(index, delta);
         } else {
             self.base.start();
         }
@@ -399,11 +1720,14 @@ where
             n
         } else {
             let delta: u64 = self.base.elapsed();
+            self.record_sample(n, delta);
             let delta = T::default().sat_add(delta);
             let mut cnt = C::default();
             cnt.sat_inc();
             self.acc_cnts.push((delta, cnt));
+            self.record_index(n);
             self.index = n + 1;
+            debug_assert!(self.index <= self.acc_cnts.len());
             n
         }
     }
@@ -423,15 +1747,51 @@ where
             n
         } else {
             let delta = self.base.elapsed_and_update();
+            self.record_sample(n, delta);
             let delta = T::default().sat_add(delta);
             let mut cnt = C::default();
             cnt.sat_inc();
             self.acc_cnts.push((delta, cnt));
+            self.record_index(n);
             self.index = n + 1;
+            debug_assert!(self.index <= self.acc_cnts.len());
             n
         }
     }
 
+    //mp record
+    /// Append a new entry with an externally measured delta (such as
+    /// from a [crate::DeltaTimer]) and a count of 1, returning its
+    /// index
+    ///
+    /// This does not use the timer of the AccVec, and always extends
+    /// the store, so that the AccVec can be used as a log of
+    /// independent measurements; `acc_cnts` then returns all of the
+    /// entries
+    ///
+    /// ```
+    /// # use cpu_timer::{AccVec, DeltaTimer};
+    /// let mut log = AccVec::<true, u64, u32>::default();
+    /// let mut t = DeltaTimer::<true>::default();
+    /// for _ in 0..3 {
+    ///     t.start();
+    ///     // do something!
+    ///     t.stop();
+    ///     log.record(t.value());
+    /// }
+    /// assert_eq!(log.acc_cnts().len(), 3);
+    /// ```
+    pub fn record(&mut self, delta: u64) -> usize {
+        let n = self.acc_cnts.len();
+        self.record_sample(n, delta);
+        let mut cnt = C::default();
+        cnt.sat_inc();
+        self.acc_cnts.push((T::default().sat_add(delta), cnt));
+        self.record_index(n);
+        self.index = n + 1;
+        n
+    }
+
     //mp all_acc_cnts
     /// Return *all* the accumulated values and counts
     ///
@@ -449,6 +1809,200 @@ where
     /// previous executions. This only returns up to the last value
     /// `pushed` since the last start.
     pub fn acc_cnts(&self) -> &[(T, C)] {
+        debug_assert!(self.index <= self.acc_cnts.len());
         &self.acc_cnts[0..self.index]
     }
+
+    //ap is_empty
+    /// Return true if nothing has been accumulated since the AccVec
+    /// was created or cleared, i.e. every count (if any) is zero
+    ///
+    /// As for [AccArray::is_empty] this is always true for a count
+    /// type of ()
+    pub fn is_empty(&self) -> bool {
+        self.acc_cnts.iter().all(|(_, c)| c.as_usize() == 0)
+    }
+
+    //ap iter_stats
+    /// Iterate over the statistics of *every* entry, including the
+    /// average
+    pub fn iter_stats(&self) -> impl Iterator<Item = AccStat<T, C>> + '_
+    where
+        T: core::ops::Div<C>,
+        C: PartialEq<C>,
+    {
+        self.acc_cnts
+            .iter()
+            .enumerate()
+            .map(|(i, (a, c))| AccStat::new(i, *a, *c))
+    }
+
+    //ap percentages
+    /// Return the accumulated value of *every* entry as a percentage of
+    /// the total accumulated across all the entries
+    ///
+    /// This does not overflow for large accumulated values; if nothing
+    /// has been accumulated then every percentage is 0
+    pub fn percentages(&self) -> Vec<f64> {
+        let total: f64 = self.acc_cnts.iter().map(|(a, _)| a.as_f64()).sum();
+        self.acc_cnts
+            .iter()
+            .map(|(a, _)| percentage(a.as_f64(), total))
+            .collect()
+    }
+
+    //ap percentiles
+    /// Return the percentiles (each 0 to 100) of the individual deltas
+    /// accumulated into an entry, using the nearest-rank method on a
+    /// sorted copy of them
+    ///
+    /// This requires the AccVec to have been created with
+    /// [AccVec::with_samples]; each percentile is 0 if no samples are
+    /// retained for the entry
+    pub fn percentiles(&self, index: usize, ps: &[f64]) -> Vec<T> {
+        let mut sorted = self
+            .samples
+            .as_ref()
+            .and_then(|s| s.get(index))
+            .cloned()
+            .unwrap_or_default();
+        sorted.sort_unstable();
+        ps.iter()
+            .map(|p| T::default().sat_add(nearest_rank(&sorted, *p)))
+            .collect()
+    }
+
+    //ap geomean
+    /// Return the geometric mean of the average value (accumulated
+    /// value divided by count) of *every* entry, skipping entries with
+    /// a count of zero
+    ///
+    /// This is the usual way to summarize a suite of benchmarks as a
+    /// single figure; it is 0 if no entry has a count
+    pub fn geomean(&self) -> f64 {
+        let (n, sum_ln) = self
+            .acc_cnts
+            .iter()
+            .filter(|(_, c)| c.as_f64() > 0.0)
+            .fold((0, 0.0), |(n, sum_ln), (a, c)| {
+                (n + 1, sum_ln + (a.as_f64() / c.as_f64()).ln())
+            });
+        if n == 0 {
+            0.0
+        } else {
+            (sum_ln / (n as f64)).exp()
+        }
+    }
+
+    //ap export
+    /// Return a [TimingReport] of the accumulated value and count of
+    /// *every* entry, labelled with its label if it has one (and
+    /// otherwise its index)
+    pub fn export(&self) -> TimingReport {
+        let mut report = TimingReport::default();
+        for (i, (a, c)) in self.acc_cnts.iter().enumerate() {
+            let label = self.label(i).map_or_else(|| i.to_string(), str::to_string);
+            let a: Delta = (*a).into();
+            report.push(label, a.into(), c.as_usize() as u64);
+        }
+        report
+    }
+
+    //mp emit_metrics
+    /// Export the accumulated value and count of *every* entry to the
+    /// `metrics` crate, as absolute counters named
+    /// `<prefix>.<label>.ticks` and `<prefix>.<label>.count`, where
+    /// the label is the index of any entry without one
+    ///
+    /// This is only available with the `metrics` feature
+    #[cfg(feature = "metrics")]
+    pub fn emit_metrics(&self, prefix: &str) {
+        for (i, (a, c)) in self.acc_cnts.iter().enumerate() {
+            let name = self.label(i).map_or_else(|| i.to_string(), str::to_string);
+            emit_metric(prefix, &name, a.as_f64(), c.as_usize());
+        }
+    }
+
+    //mp write_csv
+    /// Write the accumulated value, count and average of *every* entry
+    /// as CSV, with a header row of `index,sum,count,avg`
+    ///
+    /// If *C* is () then the count and average columns are blank
+    ///
+    /// This is only available with the `csv` feature
+    #[cfg(feature = "csv")]
+    pub fn write_csv<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        T: core::fmt::Display,
+    {
+        write_csv(w, self.acc_cnts.iter().copied())
+    }
+
+    //mp to_json
+    /// Return a JSON summary of the accumulated value, count and
+    /// average of *every* entry, as for [AccArray::to_json]
+    ///
+    /// This is only available with the `json` feature
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String
+    where
+        T: core::fmt::Display,
+    {
+        to_json(self.acc_cnts.iter().copied())
+    }
+}
+
+//ip Serialize for AccVec
+/// Only the accumulated values and counts are serialized; the timer is
+/// a live reading, and meaningless elsewhere
+#[cfg(feature = "serde")]
+impl<const S: bool, T, C, A> serde::Serialize for AccVec<S, T, C, A>
+where
+    A: TArch,
+    T: TraceValue + serde::Serialize,
+    C: TraceCount + serde::Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = serializer.serialize_struct("AccVec", 1)?;
+        st.serialize_field("acc_cnts", &self.acc_cnts)?;
+        st.end()
+    }
+}
+
+//ip Deserialize for AccVec
+/// The AccVec is created with a fresh timer, as if all the entries had
+/// been pushed since the last start
+#[cfg(feature = "serde")]
+impl<'de, const S: bool, T, C, A> serde::Deserialize<'de> for AccVec<S, T, C, A>
+where
+    A: TArch,
+    T: TraceValue + serde::Deserialize<'de>,
+    C: TraceCount + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "AccVec")]
+        struct Data<T, C> {
+            acc_cnts: Vec<(T, C)>,
+        }
+        let data = Data::<T, C>::deserialize(deserializer)?;
+        Ok(Self::from_acc_cnts(data.acc_cnts))
+    }
+}
+
+//ip Startable for AccVec
+#[cfg(feature = "std")]
+impl<const S: bool, T, C, A> Startable<S, A> for AccVec<S, T, C, A>
+where
+    A: TArch,
+    T: TraceValue,
+    C: TraceCount,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+        self.index = 0;
+        #[cfg(feature = "sequence")]
+        self.sequence.clear();
+    }
 }
@@ -0,0 +1,164 @@
+//a Imports
+use crate::hist::nearest_rank;
+use crate::{BaseTimer, HistTimer, RecordDelta, TArch, TDesc};
+
+//a Constants
+/// Number of buckets used by an [AdaptiveTimer] once it has switched
+/// to bucketed storage; with the default logarithmic edges this
+/// covers the full range of u64
+const ADAPTIVE_BUCKETS: usize = 65;
+
+/// Default number of samples an [AdaptiveTimer] stores individually
+const DEFAULT_THRESHOLD: usize = 1024;
+
+//a AdaptiveTimer
+//tp AdaptiveTimer
+/// An [AdaptiveTimer] records the times taken between 'start' and
+/// 'stop', storing each individual sample until more than a threshold
+/// number have been recorded; at that point the samples are collapsed
+/// into a [HistTimer] histogram (with logarithmic buckets), and
+/// recording continues in bounded memory
+///
+/// Percentiles are then exact for timers that are used rarely, and
+/// estimated from the histogram for timers that are used a lot.
+///
+/// ```
+/// # use cpu_timer::AdaptiveTimer;
+/// let mut t = AdaptiveTimer::<true>::with_threshold(100);
+/// for _ in 0..10 {
+///     t.start();
+///     // do something!
+///     t.stop();
+/// }
+/// assert!(!t.is_bucketed());
+/// println!("The median time was {} ticks", t.percentile(50.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdaptiveTimer<const S: bool>
+where
+    TDesc<S>: TArch,
+{
+    base: BaseTimer<S>,
+    threshold: usize,
+    samples: Vec<u64>,
+    hist: Option<HistTimer<S, ADAPTIVE_BUCKETS>>,
+}
+
+//ip Default for AdaptiveTimer
+impl<const S: bool> std::default::Default for AdaptiveTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn default() -> Self {
+        Self::with_threshold(DEFAULT_THRESHOLD)
+    }
+}
+
+//ip AdaptiveTimer
+impl<const S: bool> AdaptiveTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    //cp with_threshold
+    /// Create a new AdaptiveTimer that stores up to *threshold*
+    /// individual samples before switching to a histogram
+    pub fn with_threshold(threshold: usize) -> Self {
+        Self {
+            base: BaseTimer::default(),
+            threshold,
+            samples: vec![],
+            hist: None,
+        }
+    }
+
+    //mp clear
+    /// Clear the recorded samples, returning to per-sample storage
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.hist = None;
+    }
+
+    //mp start
+    /// Record the ticks on start to a region-to-time
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.base.start();
+    }
+
+    //mp stop
+    /// Record the delta since the last start
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        let delta = self.base.elapsed();
+        self.record(delta);
+    }
+
+    //mp record
+    /// Record a delta (e.g. measured elsewhere)
+    pub fn record(&mut self, delta: u64) {
+        if let Some(hist) = &mut self.hist {
+            hist.record(delta);
+        } else if self.samples.len() < self.threshold {
+            self.samples.push(delta);
+        } else {
+            let mut hist = HistTimer::default();
+            for s in self.samples.drain(..) {
+                hist.record(s);
+            }
+            hist.record(delta);
+            self.hist = Some(hist);
+        }
+    }
+
+    //ap is_bucketed
+    /// Return true if the timer has switched to a histogram
+    pub fn is_bucketed(&self) -> bool {
+        self.hist.is_some()
+    }
+
+    //ap count
+    /// Return the number of samples recorded
+    pub fn count(&self) -> u64 {
+        match &self.hist {
+            Some(hist) => hist.total(),
+            None => self.samples.len() as u64,
+        }
+    }
+
+    //ap samples
+    /// Return the individual samples, if the timer has not switched to
+    /// a histogram
+    pub fn samples(&self) -> Option<&[u64]> {
+        match &self.hist {
+            Some(_) => None,
+            None => Some(&self.samples),
+        }
+    }
+
+    //ap percentile
+    /// Return the *p* percentile (0 to 100) of the recorded samples
+    ///
+    /// This is exact (using the nearest-rank method) while individual
+    /// samples are stored, and an estimate from the histogram
+    /// afterwards; it is 0 if nothing has been recorded
+    pub fn percentile(&self, p: f64) -> u64 {
+        match &self.hist {
+            Some(hist) => hist.percentile(p),
+            None => {
+                let mut sorted = self.samples.clone();
+                sorted.sort_unstable();
+                nearest_rank(&sorted, p)
+            }
+        }
+    }
+}
+
+//ip RecordDelta for AdaptiveTimer
+impl<const S: bool> RecordDelta<S> for AdaptiveTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn record(&mut self, delta: u64) {
+        AdaptiveTimer::record(self, delta);
+    }
+}
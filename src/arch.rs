@@ -1,5 +1,8 @@
 //a Imports
 use crate::private;
+#[cfg(feature = "std")]
+use crate::traits::private::Value;
+use crate::{CustomCounter, TArch};
 
 //a Architecture-specific and standard get_timer functions
 //tp TDesc
@@ -15,12 +18,13 @@ use crate::private;
 ///    TDesc<S>: TArch,
 /// ```
 ///
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy)]
 pub struct TDesc<const B: bool>();
 
 //tp Asm
 /// Marker type for which TDesc is implemented for both true and false
 #[derive(Default)]
+#[allow(dead_code)]
 pub struct Asm(());
 
 //ip TArch for TDesc<true>
@@ -36,10 +40,16 @@ impl private::ArchDesc for TDesc<true> {
         arch::get_timer()
     }
 }
+impl TArch for TDesc<true> {
+    type Unit = arch::Unit;
+}
 
 //ip TArch for TDesc<false>
 // std::time implementation of a
 // timer architecture
+//
+// This requires the std feature
+#[cfg(feature = "std")]
 impl private::ArchDesc for TDesc<false> {
     type Value = arch_std::Value;
     #[inline(always)]
@@ -47,23 +57,278 @@ impl private::ArchDesc for TDesc<false> {
         arch_std::get_timer()
     }
 }
+#[cfg(feature = "std")]
+impl TArch for TDesc<false> {
+    type Unit = arch_std::Unit;
+}
+
+//tp CustomArch
+/// An architecture whose timer is a user-provided [CustomCounter],
+/// such as a memory-mapped FPGA or driver timer
+///
+/// This is given as the architecture parameter (which otherwise
+/// defaults to [TDesc]) of the timer types, whose *S* is then
+/// irrelevant; the values are in the [crate::Ticks] of the counter.
+///
+/// ```
+/// # use cpu_timer::{CustomArch, CustomCounter, DeltaTimer};
+/// struct MyCounter;
+/// impl CustomCounter for MyCounter {
+///     fn now() -> u64 {
+///         // read the memory-mapped counter
+///         # 0
+///     }
+/// }
+/// let mut t = DeltaTimer::<true, CustomArch<MyCounter>>::default();
+/// t.start();
+/// // do something!
+/// t.stop();
+/// println!("That took {} ticks of my counter", t.value());
+/// ```
+pub struct CustomArch<C: CustomCounter>(core::marker::PhantomData<C>);
+
+//ip Default for CustomArch
+impl<C: CustomCounter> core::default::Default for CustomArch<C> {
+    fn default() -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+//ip Debug for CustomArch
+impl<C: CustomCounter> core::fmt::Debug for CustomArch<C> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        fmt.write_str("CustomArch")
+    }
+}
+
+//ip Clone for CustomArch
+impl<C: CustomCounter> core::clone::Clone for CustomArch<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+//ip Copy for CustomArch
+impl<C: CustomCounter> core::marker::Copy for CustomArch<C> {}
+
+//ip TArch for CustomArch
+impl<C: CustomCounter> private::ArchDesc for CustomArch<C> {
+    type Value = u64;
+    #[inline(always)]
+    fn get_timer() -> Self::Value {
+        C::now()
+    }
+}
+impl<C: CustomCounter> TArch for CustomArch<C> {
+    type Unit = crate::Ticks;
+}
+
+//fp uses_asm
+/// Return true if timers generic on *S* use an architecture specific
+/// (assembler) timer implementation
+///
+/// This is true only if *S* is true *and* the target architecture has
+/// an assembler implementation; for architectures without one, the
+/// std::time implementation is used whatever the value of *S*.
+///
+/// ```
+/// # use cpu_timer::uses_asm;
+/// assert!(!uses_asm::<false>());
+/// if uses_asm::<true>() {
+///     println!("Timer<true> is using the CPU tick counter");
+/// }
+/// ```
+pub const fn uses_asm<const S: bool>() -> bool {
+    S && arch::HAS_ASM
+}
+
+//fp tsc_is_invariant
+/// Return true if the architecture specific (assembler) timer ticks
+/// at a constant rate, independent of CPU frequency and power states
+///
+/// On x86 and x86_64 this checks the 'invariant TSC' CPUID flag
+/// (which some virtual machines do not report); on aarch64 with the
+/// `aarch64-pmccntr` feature, and on riscv without the `riscv-rdtime`
+/// feature, the counter counts CPU cycles, and this is false; on
+/// other architectures the counter is a fixed-frequency system
+/// counter, or the std::time implementation is used, and this is
+/// always true.
+pub fn tsc_is_invariant() -> bool {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::__cpuid;
+        #[allow(unused_unsafe)]
+        let max_extended_leaf = unsafe { __cpuid(0x8000_0000) }.eax;
+        if max_extended_leaf < 0x8000_0007 {
+            return false;
+        }
+        #[allow(unused_unsafe)]
+        let edx = unsafe { __cpuid(0x8000_0007) }.edx;
+        edx & (1 << 8) != 0
+    }
+    #[cfg(any(
+        all(target_arch = "aarch64", feature = "aarch64-pmccntr"),
+        all(
+            any(target_arch = "riscv32", target_arch = "riscv64"),
+            not(feature = "riscv-rdtime")
+        )
+    ))]
+    {
+        false
+    }
+    #[cfg(not(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "aarch64", feature = "aarch64-pmccntr"),
+        all(
+            any(target_arch = "riscv32", target_arch = "riscv64"),
+            not(feature = "riscv-rdtime")
+        )
+    )))]
+    {
+        true
+    }
+}
+
+//fp counter_frequency
+/// Return the frequency in Hz of the architecture specific (assembler)
+/// timer, if the architecture provides it
+///
+/// On aarch64 (without the `aarch64-pmccntr` feature) this reads
+/// `cntfrq_el0`, the frequency of the virtual counter (24MHz on Apple
+/// silicon, for example); where the ticks are nanoseconds (for the
+/// std::time implementation, or `wasm-perf`) this is 1GHz. Other
+/// counters (such as the x86 TSC, or a CPU cycle counter) have no
+/// architectural means to query their frequency, and this returns
+/// None; a [crate::Calibration] can be measured instead.
+///
+/// ```
+/// # use cpu_timer::counter_frequency;
+/// if let Some(hz) = counter_frequency() {
+///     println!("The counter ticks at {hz}Hz");
+/// }
+/// ```
+pub fn counter_frequency() -> Option<u64> {
+    #[cfg(all(target_arch = "aarch64", not(feature = "aarch64-pmccntr")))]
+    {
+        arch::counter_frequency()
+    }
+    #[cfg(all(target_arch = "wasm32", feature = "wasm-perf"))]
+    {
+        Some(1_000_000_000)
+    }
+    #[cfg(not(any(
+        all(target_arch = "aarch64", not(feature = "aarch64-pmccntr")),
+        all(target_arch = "wasm32", feature = "wasm-perf")
+    )))]
+    {
+        if arch::HAS_ASM {
+            None
+        } else {
+            Some(1_000_000_000)
+        }
+    }
+}
+
+//fp probe_asm_timer
+/// Probe (once; the result is cached) whether the architecture
+/// specific (assembler) timer is usable, on a best-effort basis
+///
+/// This returns false if the target architecture has no assembler
+/// implementation, or if the CPU counter does not advance over a few
+/// milliseconds of std::time (as can occur in some virtualized or
+/// restricted environments); an application can then fall back to
+/// the std::time implementation gracefully.
+///
+/// Note that this cannot guard against the counter read *trapping*
+/// (as it may on aarch64 kernels that deny userspace access to
+/// `cntvct_el0`, or riscv kernels that deny access to the `cycle`
+/// CSR): a trap raises SIGILL, which cannot be caught here,
+/// and the process will terminate during the probe. If that is a
+/// possibility then the probe should be run in a child process.
+#[cfg(feature = "std")]
+pub fn probe_asm_timer() -> bool {
+    static PROBE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *PROBE.get_or_init(|| {
+        if !arch::HAS_ASM {
+            return false;
+        }
+        let start = std::time::Instant::now();
+        let t0 = <TDesc<true> as private::ArchDesc>::get_timer().ticks();
+        while start.elapsed() < std::time::Duration::from_millis(10) {
+            let t1 = <TDesc<true> as private::ArchDesc>::get_timer().ticks();
+            if t1 != t0 {
+                return t1.wrapping_sub(t0) < (1 << 63);
+            }
+            core::hint::spin_loop();
+        }
+        false
+    })
+}
 
 //a Architecture specific and standard timer implementation modules
 //mi Standard architecture implementation of a timer
+#[cfg(all(
+    feature = "std",
+    not(any(
+        all(feature = "cpu-time", any(target_os = "linux", target_os = "macos")),
+        all(feature = "vdso", target_os = "linux")
+    ))
+))]
 mod arch_std {
+    /// This is not an assembler implementation
+    #[allow(dead_code)]
+    pub const HAS_ASM: bool = false;
+    pub type Unit = crate::Nanos;
+
+    //fi epoch
+    /// The epoch from which std::time values are converted to ticks
+    ///
+    /// This is captured on first use; values from before then are
+    /// converted to ticks by wrapping, so that the difference between
+    /// any two tick values is still correct
+    fn epoch() -> std::time::Instant {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        *EPOCH.get_or_init(std::time::Instant::now)
+    }
+
     #[derive(Debug, Clone, Copy)]
     pub struct Value(std::time::Instant);
     impl super::private::Value for Value {
+        fn ticks(self) -> u64 {
+            let epoch = epoch();
+            if self.0 >= epoch {
+                (self.0 - epoch).as_nanos() as u64
+            } else {
+                ((epoch - self.0).as_nanos() as u64).wrapping_neg()
+            }
+        }
+        fn from_ticks(ticks: u64) -> Self {
+            let epoch = epoch();
+            let nanos = ticks as i64;
+            if nanos >= 0 {
+                Self(epoch + std::time::Duration::from_nanos(nanos as u64))
+            } else {
+                let before = std::time::Duration::from_nanos(nanos.unsigned_abs());
+                Self(epoch.checked_sub(before).unwrap_or(epoch))
+            }
+        }
+        // A backwards Instant should not occur, but on some platforms
+        // it may after a rare clock glitch; it gives a zero delta
+        // rather than a panic
         fn since(self, last: Self) -> crate::Delta {
-            (self.0 - last.0).as_nanos().into()
+            self.0.saturating_duration_since(last.0).as_nanos().into()
         }
         fn since_and_update(&mut self, now: Self) -> crate::Delta {
-            let delta = (now.0 - self.0).as_nanos().into();
+            let delta = now.0.saturating_duration_since(self.0).as_nanos().into();
             *self = now;
             delta
         }
     }
-    impl std::default::Default for Value {
+    impl core::default::Default for Value {
         fn default() -> Self {
             Self(std::time::Instant::now())
         }
@@ -74,16 +339,217 @@ mod arch_std {
     }
 }
 
+//mi Thread CPU time implementation of a timer
+/// With the `cpu-time` feature the std::time implementation of a
+/// timer is replaced by the CPU time consumed by the calling thread,
+/// in nanoseconds; time that the thread is descheduled is then not
+/// counted
+///
+/// On Linux this uses `clock_gettime(CLOCK_THREAD_CPUTIME_ID)`, which
+/// is a system call (the VDSO does not support the thread CPU time
+/// clocks). On MacOs this uses the same call, which the system
+/// implements using `thread_info`.
+///
+/// On other operating systems the feature has no effect.
+#[cfg(all(feature = "cpu-time", any(target_os = "linux", target_os = "macos")))]
+mod arch_std {
+    /// This is not an assembler implementation
+    #[allow(dead_code)]
+    pub const HAS_ASM: bool = false;
+    pub type Unit = crate::Nanos;
+
+    /// The thread CPU time in nanoseconds, as a newtype so that its
+    /// default is the current time; a timer that has not been started
+    /// then measures from its construction, rather than reporting the
+    /// whole CPU time of the thread
+    #[derive(Debug, Clone, Copy)]
+    pub struct Value(u64);
+    impl super::private::Value for Value {
+        fn ticks(self) -> u64 {
+            self.0
+        }
+        fn from_ticks(ticks: u64) -> Self {
+            Self(ticks)
+        }
+        fn since(self, last: Self) -> crate::Delta {
+            super::private::Value::since(self.0, last.0)
+        }
+        fn since_and_update(&mut self, now: Self) -> crate::Delta {
+            super::private::Value::since_and_update(&mut self.0, now.0)
+        }
+    }
+    impl core::default::Default for Value {
+        fn default() -> Self {
+            get_timer()
+        }
+    }
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        unsafe {
+            libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts);
+        }
+        Value((ts.tv_sec as u64) * 1_000_000_000 + (ts.tv_nsec as u64))
+    }
+}
+
+//mi VDSO monotonic clock implementation of a timer
+/// With the `vdso` feature on Linux the std::time implementation of a
+/// timer calls `clock_gettime(CLOCK_MONOTONIC)` directly, in
+/// nanoseconds; this is the same clock that `std::time::Instant` uses,
+/// and it is also implemented in the VDSO (so there is no system
+/// call), but the value is kept as a plain u64 rather than converted
+/// to and from an `Instant`
+///
+/// The `overhead` example compares this with `std::time::Instant`.
+///
+/// If the `cpu-time` feature is also enabled then that takes
+/// precedence; on other operating systems the feature has no effect.
+#[cfg(all(feature = "vdso", target_os = "linux", not(feature = "cpu-time")))]
+mod arch_std {
+    /// This is not an assembler implementation
+    #[allow(dead_code)]
+    pub const HAS_ASM: bool = false;
+    pub type Unit = crate::Nanos;
+
+    /// The clock in nanoseconds, as a newtype so that its default is
+    /// the current time (as for `std::time::Instant`); a timer that has
+    /// not been started then measures from its construction
+    #[derive(Debug, Clone, Copy)]
+    pub struct Value(u64);
+    impl super::private::Value for Value {
+        fn ticks(self) -> u64 {
+            self.0
+        }
+        fn from_ticks(ticks: u64) -> Self {
+            Self(ticks)
+        }
+        fn since(self, last: Self) -> crate::Delta {
+            super::private::Value::since(self.0, last.0)
+        }
+        fn since_and_update(&mut self, now: Self) -> crate::Delta {
+            super::private::Value::since_and_update(&mut self.0, now.0)
+        }
+    }
+    impl core::default::Default for Value {
+        fn default() -> Self {
+            get_timer()
+        }
+    }
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        unsafe {
+            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts);
+        }
+        Value((ts.tv_sec as u64) * 1_000_000_000 + (ts.tv_nsec as u64))
+    }
+}
+
 //mi get_timer for OTHER architectures
-#[cfg(not(any(target_arch = "aarch64", target_arch = "x86_64",)))]
+#[cfg(all(
+    feature = "std",
+    not(any(
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", feature = "wasm-perf")
+    ))
+))]
 use arch_std as arch;
 
+//fi get_timer for wasm32
+/// With the `wasm-perf` feature on wasm32 the 'CPU specific' timer is
+/// the browser `performance.now()`, a floating point number of
+/// milliseconds, which is converted to ticks of nanoseconds
+///
+/// The `Performance` object is obtained from the window once per
+/// thread; if there is none (e.g. outside a browser window) then the
+/// timer always reads 0. The resolution of `performance.now()` may be
+/// deliberately coarsened by the browser.
+#[cfg(all(target_arch = "wasm32", feature = "wasm-perf"))]
+mod arch {
+    pub const HAS_ASM: bool = true;
+    pub type Unit = crate::Nanos;
+
+    thread_local! {
+        static PERFORMANCE: Option<web_sys::Performance> =
+            web_sys::window().and_then(|w| w.performance());
+    }
+
+    /// Convert a (non-negative) number of milliseconds to nanoseconds
+    fn ms_to_ns(ms: f64) -> u64 {
+        (ms.max(0.0) * 1_000_000.0) as u64
+    }
+
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Value(f64);
+    impl super::private::Value for Value {
+        fn ticks(self) -> u64 {
+            ms_to_ns(self.0)
+        }
+        fn from_ticks(ticks: u64) -> Self {
+            Self(ticks as f64 / 1_000_000.0)
+        }
+        fn since(self, last: Self) -> crate::Delta {
+            ms_to_ns(self.0 - last.0).into()
+        }
+        fn since_and_update(&mut self, now: Self) -> crate::Delta {
+            let delta = ms_to_ns(now.0 - self.0).into();
+            *self = now;
+            delta
+        }
+    }
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        Value(PERFORMANCE.with(|p| p.as_ref().map_or(0.0, |p| p.now())))
+    }
+}
+
 //fi get_timer for Aarch64
 /// Known to work on Apple M4 (MacbookPro 2024)
+///
+/// By default this reads the virtual counter `cntvct_el0`, which is a
+/// fixed-frequency system counter; that frequency may be much lower
+/// than that of the CPU (giving a granularity of about 40 CPU cycles
+/// on an M4).
+///
+/// With the `aarch64-pmccntr` feature it reads the PMU cycle counter
+/// `pmccntr_el0` instead, which counts CPU cycles (and so is not
+/// invariant). This is only accessible at EL0 if the PMU has been
+/// enabled and user access granted at EL1 or above (PMCR_EL0.E,
+/// PMCNTENSET_EL0.C and PMUSERENR_EL0.EN); otherwise the read traps
+/// and the process or firmware faults. It is intended for bare-metal
+/// and embedded use where the exception levels are under the control
+/// of the application; most operating systems do not grant the access.
 #[cfg(target_arch = "aarch64")]
 mod arch {
-    use std::arch::asm;
+    use core::arch::asm;
+    pub const HAS_ASM: bool = true;
+    pub type Unit = crate::Ticks;
     pub type Value = u64;
+    #[cfg(feature = "aarch64-pmccntr")]
+    #[inline(always)]
+    pub fn get_timer() -> u64 {
+        let timer: u64;
+        unsafe {
+            asm!(
+                "isb
+                mrs {timer}, pmccntr_el0",
+                timer = out(reg) timer,
+            );
+        }
+        timer
+    }
+    #[cfg(not(feature = "aarch64-pmccntr"))]
     #[inline(always)]
     pub fn get_timer() -> u64 {
         let timer: u64;
@@ -96,14 +562,168 @@ mod arch {
         }
         timer
     }
+    /// The frequency of `cntvct_el0`, or None if firmware has not set
+    /// `cntfrq_el0`
+    #[cfg(not(feature = "aarch64-pmccntr"))]
+    pub fn counter_frequency() -> Option<u64> {
+        let freq: u64;
+        unsafe {
+            asm!(
+                "mrs {freq}, cntfrq_el0",
+                freq = out(reg) freq,
+                options(nomem, nostack)
+            );
+        }
+        (freq != 0).then_some(freq)
+    }
+}
+
+//fi get_timer for riscv
+/// Not tested yet
+///
+/// By default this reads the `cycle` CSR, which counts CPU cycles (and
+/// so is not invariant). Linux from 6.6 denies userspace access to
+/// this by default (it traps, raising SIGILL) unless
+/// `/proc/sys/kernel/perf_user_access` is set to 2; in that case, or
+/// on other environments that trap the read, the `riscv-rdtime`
+/// feature reads the `time` CSR instead, which is a fixed-frequency
+/// counter that Linux always permits userspace to read.
+///
+/// On riscv32 the counter is read as two 32-bit halves, rereading the
+/// high half (and retrying should it have changed) so that a carry
+/// between the two reads cannot produce a torn value.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+mod arch {
+    use core::arch::asm;
+    pub const HAS_ASM: bool = true;
+    pub type Unit = crate::Ticks;
+    pub type Value = u64;
+
+    /// Read a counter CSR of the native width, by its (pseudo-)instruction
+    macro_rules! read_csr {
+        ($insn:literal) => {{
+            let value: usize;
+            unsafe {
+                asm!(
+                    $insn,
+                    value = out(reg) value,
+                    options(nomem, nostack)
+                );
+            }
+            value
+        }};
+    }
+
+    #[cfg(all(target_arch = "riscv64", not(feature = "riscv-rdtime")))]
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        read_csr!("rdcycle {value}") as u64
+    }
+
+    #[cfg(all(target_arch = "riscv64", feature = "riscv-rdtime"))]
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        read_csr!("rdtime {value}") as u64
+    }
+
+    #[cfg(all(target_arch = "riscv32", not(feature = "riscv-rdtime")))]
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        loop {
+            let hi = read_csr!("rdcycleh {value}");
+            let lo = read_csr!("rdcycle {value}");
+            if read_csr!("rdcycleh {value}") == hi {
+                return (hi as u64) << 32 | (lo as u64);
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "riscv32", feature = "riscv-rdtime"))]
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        loop {
+            let hi = read_csr!("rdtimeh {value}");
+            let lo = read_csr!("rdtime {value}");
+            if read_csr!("rdtimeh {value}") == hi {
+                return (hi as u64) << 32 | (lo as u64);
+            }
+        }
+    }
+}
+
+//fi get_timer for x86
+/// Not tested yet
+///
+/// This is an `lfence; rdtsc`, so that the read is ordered after all
+/// preceding instructions have completed
+#[cfg(target_arch = "x86")]
+mod arch {
+    use core::arch::asm;
+    pub const HAS_ASM: bool = true;
+    pub type Unit = crate::Ticks;
+    pub type Value = u64;
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        let lo: u32;
+        let hi: u32;
+        unsafe {
+            asm!(
+                "
+                lfence
+                rdtsc
+                ",
+                lateout("eax") lo,
+                lateout("edx") hi,
+              options(nomem, nostack)
+            );
+        }
+        (hi as u64) << 32 | (lo as u64)
+    }
 }
 
 //fi get_timer for x86_64
 /// Not tested yet
+///
+/// By default this is a plain `rdtsc`, which is not ordered with
+/// respect to other instructions: the CPU may execute it before
+/// preceding instructions complete, or after later ones start.
+///
+/// With the `x86-mfence` feature the read is `mfence; lfence; rdtsc`,
+/// and the compiler is told that the read may access memory. The
+/// `mfence` ensures that all preceding loads and stores are globally
+/// visible (i.e. to other cores) before the counter is read, and the
+/// `lfence` then ensures that all preceding instructions have
+/// completed (an `lfence` alone orders the read against preceding
+/// instructions, but does not wait for stores to drain). This gives
+/// the ordering required when correlating a timer read with
+/// shared-memory operations (such as in lock-free algorithms), at a
+/// significantly higher overhead; the `overhead` example measures it.
 #[cfg(target_arch = "x86_64")]
 mod arch {
-    use std::arch::asm;
+    use core::arch::asm;
+    pub const HAS_ASM: bool = true;
+    pub type Unit = crate::Ticks;
     pub type Value = u64;
+    #[cfg(feature = "x86-mfence")]
+    #[inline(always)]
+    pub fn get_timer() -> Value {
+        let lo: u64;
+        let hi: u64;
+        unsafe {
+            asm!(
+                "
+                mfence
+                lfence
+                rdtsc
+                ",
+                lateout("eax") lo,
+                lateout("edx") hi,
+              options(nostack)
+            );
+        }
+        hi << 32 | lo
+    }
+    #[cfg(not(feature = "x86-mfence"))]
     #[inline(always)]
     pub fn get_timer() -> Value {
         let lo: u64;
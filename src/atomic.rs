@@ -0,0 +1,96 @@
+//a Imports
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{BaseTimer, TArch, TDesc};
+
+//a AtomicAccTimer
+//tp AtomicAccTimer
+/// An [AtomicAccTimer] accumulates the time deltas of a region of code
+/// that is executed by many threads, in one shared accumulator
+///
+/// As the timer is shared, it cannot hold the start of each thread's
+/// region; instead 'start' returns a token (the ticks at the start),
+/// which the thread passes back to 'stop'. The accumulated value and
+/// count are updated with relaxed atomic additions, which wrap on
+/// overflow (unlike the other accumulators, which saturate).
+///
+/// ```
+/// # use cpu_timer::AtomicAccTimer;
+/// static T: AtomicAccTimer<true> = AtomicAccTimer::new();
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let token = T.start();
+///             // do something!
+///             T.stop(token);
+///         });
+///     }
+/// });
+/// assert_eq!(T.count(), 4);
+/// println!("The threads took {} ticks in total", T.acc_value());
+/// ```
+#[derive(Debug, Default)]
+pub struct AtomicAccTimer<const S: bool>
+where
+    TDesc<S>: TArch,
+{
+    acc: AtomicU64,
+    count: AtomicU64,
+}
+
+//ip AtomicAccTimer
+impl<const S: bool> AtomicAccTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    //cp new
+    /// Create a new timer, in a const context (such as for a `static`)
+    pub const fn new() -> Self {
+        Self {
+            acc: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    //mp clear
+    /// Clear the accumulated value and count
+    pub fn clear(&self) {
+        self.acc.store(0, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+    }
+
+    //mp start
+    /// Return a token for the start of a region-to-time, to be passed
+    /// to 'stop'
+    #[inline(always)]
+    pub fn start(&self) -> u64 {
+        BaseTimer::<S>::now_ticks()
+    }
+
+    //mp stop
+    /// Accumulate the delta since the 'start' that returned *token*,
+    /// and count it
+    #[inline(always)]
+    pub fn stop(&self, token: u64) {
+        let delta = BaseTimer::<S>::now_ticks().wrapping_sub(token);
+        self.acc.fetch_add(delta, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    //ap acc_value
+    /// Read the accumulator value
+    ///
+    /// As the value and count are updated separately, a concurrent
+    /// 'stop' may have updated one but not yet the other
+    #[inline(always)]
+    pub fn acc_value(&self) -> u64 {
+        self.acc.load(Ordering::Relaxed)
+    }
+
+    //ap count
+    /// Return the number of deltas accumulated since the last clear
+    #[inline(always)]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
@@ -1,30 +1,103 @@
 //a Imports
 use crate::private;
 use crate::traits::private::Value;
-use crate::{Delta, TArch, TDesc};
+use crate::{Delta, Startable, TArch, TDesc};
+
+//a Functions
+//fp start_all
+/// Start all of the timers with the same start time, reading the
+/// timer just once
+///
+/// Starting several timers in turn with their `start` methods gives
+/// each a slightly different start; this removes that skew, so that
+/// measurements from the timers can be correlated
+///
+/// ```
+/// # use cpu_timer::{start_all, AccArray, AccVec};
+/// let mut a = AccArray::<true, u64, u32, 4>::default();
+/// let mut v = AccVec::<true, u64, u32>::default();
+/// start_all::<true>(&mut [&mut a, &mut v]);
+/// ```
+pub fn start_all<const S: bool>(timers: &mut [&mut dyn Startable<S>])
+where
+    TDesc<S>: TArch,
+{
+    start_all_arch::<S, TDesc<S>>(timers);
+}
+
+//fp start_all_arch
+/// Start all of the timers of an architecture (such as a
+/// [crate::CustomArch]) with the same start time, reading the timer
+/// of that architecture just once
+///
+/// This is as [start_all], which is this for the architecture selected
+/// by *S*
+///
+/// ```
+/// # use cpu_timer::{start_all_arch, AccArray, AccTimer, CustomArch, CustomCounter};
+/// struct MyCounter;
+/// impl CustomCounter for MyCounter {
+///     fn now() -> u64 {
+///         // read the memory-mapped counter
+///         # 0
+///     }
+/// }
+/// type Arch = CustomArch<MyCounter>;
+/// let mut a = AccArray::<true, u64, u32, 4, Arch>::default();
+/// let mut t = AccTimer::<true, Arch>::default();
+/// start_all_arch::<true, Arch>(&mut [&mut a, &mut t]);
+/// ```
+pub fn start_all_arch<const S: bool, A: TArch>(timers: &mut [&mut dyn Startable<S, A>]) {
+    let ticks = BaseTimer::<S, A>::now_ticks();
+    for t in timers.iter_mut() {
+        t.start_at(ticks);
+    }
+}
 
 //a BaseTimer
 //tp BaseTimer
 /// A basic timer that just contains the timer value
 ///
-/// This is used internally for all the timer implementations
+/// This is used internally for all the timer implementations; the
+/// architecture *A* defaults to that selected by *S*, and may be a
+/// [crate::CustomArch]
 #[derive(Default, Debug, Clone, Copy)]
-pub struct BaseTimer<const S: bool>
-where
-    TDesc<S>: TArch,
-{
-    start: <TDesc<S> as private::ArchDesc>::Value,
+pub struct BaseTimer<const S: bool, A: TArch = TDesc<S>> {
+    start: <A as private::ArchDesc>::Value,
+}
+
+//ip BaseTimer<true>
+/// On architectures with an assembler timer the value is a plain u64,
+/// and so a timer can be created in a const context (such as for a
+/// `static`); the start is then 0 until the first `start`
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+))]
+impl BaseTimer<true> {
+    //cp new
+    /// Create a new timer with a start of 0, in a const context
+    pub const fn new() -> Self {
+        Self { start: 0 }
+    }
 }
 
 //ip BaseTimer
-impl<const S: bool> BaseTimer<S>
-where
-    TDesc<S>: TArch,
-{
+impl<const S: bool, A: TArch> BaseTimer<S, A> {
     //mi now
     #[inline(always)]
-    fn now() -> <TDesc<S> as private::ArchDesc>::Value {
-        <TDesc<S> as private::ArchDesc>::get_timer()
+    fn now() -> <A as private::ArchDesc>::Value {
+        <A as private::ArchDesc>::get_timer()
+    }
+
+    //fp now_ticks
+    /// Return the timer value now as a u64 tick count
+    #[inline(always)]
+    pub(crate) fn now_ticks() -> u64 {
+        Self::now().ticks()
     }
 
     //mp start
@@ -34,6 +107,20 @@ where
         self.start = Self::now();
     }
 
+    //mp start_at
+    /// Set the start time from a u64 tick count
+    #[inline(always)]
+    pub(crate) fn start_at(&mut self, ticks: u64) {
+        self.start = <A as private::ArchDesc>::Value::from_ticks(ticks);
+    }
+
+    //ap start_ticks
+    /// Return the start time as a u64 tick count
+    #[inline(always)]
+    pub(crate) fn start_ticks(&self) -> u64 {
+        self.start.ticks()
+    }
+
     //mp elapsed_delta
     /// Return the Delta between now and self.start
     #[inline(always)]
@@ -41,6 +128,20 @@ where
         Self::now().since(self.start)
     }
 
+    //mp elapsed_checked
+    /// Return the ticks between self.start and now, or None if now is
+    /// before the start (which, for a monotonic counter, indicates that
+    /// the counter has wrapped)
+    #[inline(always)]
+    pub(crate) fn elapsed_checked(&self) -> Option<u64> {
+        let delta = Self::now_ticks().wrapping_sub(self.start_ticks());
+        if (delta as i64) < 0 {
+            None
+        } else {
+            Some(delta)
+        }
+    }
+
     //mp elapsed_delta_and_update
     /// Record the delta time since the last start
     #[inline(always)]
@@ -0,0 +1,139 @@
+//a Imports
+use std::time::{Duration, Instant};
+
+use crate::{uses_asm, BaseTimer, Timer};
+
+//a Constants
+/// The time spent busy-spinning for each sample when calibrating
+/// without a gap
+const SPIN_SAMPLE: Duration = Duration::from_millis(1);
+
+//a Calibration
+//tp Calibration
+/// A [Calibration] records the number of CPU counter ticks per
+/// nanosecond of wall clock time, for the asm timers (i.e. with
+/// *UseAsm* true)
+///
+/// For architectures without an asm timer the 'ticks' are nanoseconds,
+/// and a calibration should be (close to) 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    ticks_per_nanosecond: f64,
+}
+
+//ip Calibration
+impl Calibration {
+    //cp measure
+    /// Calibrate the CPU counter against `std::time::Instant` by
+    /// busy-spinning a [Timer] for *duration*
+    ///
+    /// If the asm timer is not in use (so that its ticks are
+    /// nanoseconds) then this does not spin, and the calibration is
+    /// exactly 1.0
+    ///
+    /// ```
+    /// # use cpu_timer::Calibration;
+    /// # use std::time::Duration;
+    /// let c = Calibration::measure(Duration::from_millis(10));
+    /// println!("1000 ticks is {:?}", c.ticks_to_duration(1000));
+    /// ```
+    pub fn measure(duration: Duration) -> Self {
+        if !uses_asm::<true>() {
+            return Self {
+                ticks_per_nanosecond: 1.0,
+            };
+        }
+        let mut timer = Timer::<true>::default();
+        let start = Instant::now();
+        timer.start();
+        while start.elapsed() < duration {
+            std::hint::spin_loop();
+        }
+        let ticks = timer.elapsed();
+        let nanos = start.elapsed().as_nanos() as f64;
+        let ticks_per_nanosecond = if nanos > 0.0 && ticks > 0 {
+            ticks as f64 / nanos
+        } else {
+            1.0
+        };
+        Self {
+            ticks_per_nanosecond,
+        }
+    }
+
+    //cp calibrate_steady
+    /// Calibrate the CPU counter against `std::time::Instant`, taking
+    /// samples spread over *duration* with a pause of *gap* between
+    /// each
+    ///
+    /// The ratio of ticks to nanoseconds is measured for each sample,
+    /// and the median is used. Pausing (sleeping) between samples
+    /// stops the calibration itself from boosting the clock or
+    /// throttling the CPU, so that on frequency-scaling CPUs the result
+    /// is more representative of the steady state; with a zero *gap*
+    /// each sample is instead a short busy-spin (with
+    /// `std::hint::spin_loop`).
+    ///
+    /// ```
+    /// # use cpu_timer::Calibration;
+    /// # use std::time::Duration;
+    /// let c = Calibration::calibrate_steady(Duration::from_millis(20), Duration::from_millis(2));
+    /// println!("{} ticks per ns", c.ticks_per_nanosecond());
+    /// ```
+    pub fn calibrate_steady(duration: Duration, gap: Duration) -> Self {
+        let end = Instant::now() + duration;
+        let mut ratios = vec![];
+        let mut last = (Instant::now(), BaseTimer::<true>::now_ticks());
+        loop {
+            if gap.is_zero() {
+                while last.0.elapsed() < SPIN_SAMPLE {
+                    std::hint::spin_loop();
+                }
+            } else {
+                std::thread::sleep(gap);
+            }
+            let now = (Instant::now(), BaseTimer::<true>::now_ticks());
+            let nanos = (now.0 - last.0).as_nanos() as f64;
+            if nanos > 0.0 {
+                ratios.push(now.1.wrapping_sub(last.1) as f64 / nanos);
+            }
+            last = now;
+            if last.0 >= end {
+                break;
+            }
+        }
+        ratios.sort_unstable_by(f64::total_cmp);
+        let ticks_per_nanosecond = ratios.get(ratios.len() / 2).copied().unwrap_or(1.0);
+        Self {
+            ticks_per_nanosecond,
+        }
+    }
+
+    //ap ticks_per_nanosecond
+    /// Return the number of CPU counter ticks per nanosecond
+    pub fn ticks_per_nanosecond(&self) -> f64 {
+        self.ticks_per_nanosecond
+    }
+
+    //ap hz
+    /// Return the effective frequency of the CPU counter in Hz (ticks
+    /// per second)
+    ///
+    /// Where the architecture provides it [crate::counter_frequency]
+    /// can be used as a cross-check
+    pub fn hz(&self) -> f64 {
+        self.ticks_per_nanosecond * 1e9
+    }
+
+    //ap ticks_to_nanos
+    /// Convert a number of CPU counter ticks to nanoseconds
+    pub fn ticks_to_nanos(&self, ticks: u64) -> f64 {
+        ticks as f64 / self.ticks_per_nanosecond
+    }
+
+    //ap ticks_to_duration
+    /// Convert a number of CPU counter ticks to a `Duration`
+    pub fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        Duration::from_nanos(self.ticks_to_nanos(ticks) as u64)
+    }
+}
@@ -10,6 +10,9 @@ pub(crate) struct Delta(u64);
 
 //ip Delta
 impl Delta {
+    /// A zero delta, for use in const contexts
+    pub const ZERO: Self = Self(0);
+
     //cp add
     /// Accmulate another delta into this value
     #[inline(always)]
@@ -31,7 +34,7 @@ impl Delta {
 impl From<()> for Delta {
     #[inline(always)]
     fn from(_t: ()) -> Self {
-        Self(0)
+        Self::ZERO
     }
 }
 
@@ -0,0 +1,104 @@
+//a Imports
+use crate::DeltaTimer;
+
+//a DynTimer
+//tp DynTimer
+/// A [DynTimer] wraps either an asm or a std::time [DeltaTimer],
+/// selected at runtime, with methods that dispatch on the variant
+///
+/// This allows a single non-generic function to accept either kind of
+/// timer (e.g. at plugin or FFI boundaries, where const generics
+/// cannot cross), and the kind to be chosen at runtime (such as with
+/// [crate::probe_asm_timer]), at the cost of a branch on each
+/// operation.
+///
+/// ```
+/// # use cpu_timer::{probe_asm_timer, DynTimer};
+/// fn profile(t: &mut DynTimer) -> u64 {
+///     t.start();
+///     // do something!
+///     t.stop();
+///     t.value()
+/// }
+/// let mut t = DynTimer::new(probe_asm_timer());
+/// println!("That took {} ticks", profile(&mut t));
+/// let mut t = DynTimer::new(false);
+/// println!("That took {} nanoseconds", profile(&mut t));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub enum DynTimer {
+    /// An architecture-specific (asm) timer
+    Asm(DeltaTimer<true>),
+    /// A std::time timer
+    Std(DeltaTimer<false>),
+}
+
+//ip DynTimer
+impl DynTimer {
+    //cp new
+    /// Create a new timer, using the asm timer if *use_asm* is true,
+    /// otherwise the std::time timer
+    pub fn new(use_asm: bool) -> Self {
+        if use_asm {
+            Self::Asm(DeltaTimer::default())
+        } else {
+            Self::Std(DeltaTimer::default())
+        }
+    }
+
+    //ap is_asm
+    /// Return true if this wraps the asm timer
+    pub fn is_asm(&self) -> bool {
+        matches!(self, Self::Asm(_))
+    }
+
+    //mp start
+    /// Record the ticks at the start of the timer
+    #[inline(always)]
+    pub fn start(&mut self) {
+        match self {
+            Self::Asm(t) => t.start(),
+            Self::Std(t) => t.start(),
+        }
+    }
+
+    //mp stop
+    /// Record the delta time since the last start
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        match self {
+            Self::Asm(t) => t.stop(),
+            Self::Std(t) => t.stop(),
+        }
+    }
+
+    //ap value
+    /// Return the delta time recorded by the last stop
+    #[inline(always)]
+    pub fn value(&self) -> u64 {
+        match self {
+            Self::Asm(t) => t.value(),
+            Self::Std(t) => t.value(),
+        }
+    }
+
+    //ap elapsed
+    /// Return (without updating) the time elapsed since start as a u64
+    #[inline(always)]
+    pub fn elapsed(&self) -> u64 {
+        match self {
+            Self::Asm(t) => t.delta(),
+            Self::Std(t) => t.delta(),
+        }
+    }
+
+    //mp elapsed_and_update
+    /// Return the time elapsed as a u64, and restart the timer from now
+    #[inline(always)]
+    pub fn elapsed_and_update(&mut self) -> u64 {
+        match self {
+            Self::Asm(t) => t.elapsed_and_update(),
+            Self::Std(t) => t.elapsed_and_update(),
+        }
+    }
+}
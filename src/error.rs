@@ -0,0 +1,32 @@
+//a TimerError
+//tp TimerError
+/// An error indicating that a timer cannot be relied upon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerError {
+    /// The CPU counter used by the asm timer is not invariant (see
+    /// [crate::tsc_is_invariant]), so its rate may change with CPU
+    /// frequency and power states
+    NonInvariantCounter,
+    /// The timer reading at a stop was before that at the start, so
+    /// the counter has wrapped (or the interval is implausibly long)
+    Wraparound,
+}
+
+//ip Display for TimerError
+impl core::fmt::Display for TimerError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        match self {
+            Self::NonInvariantCounter => write!(
+                fmt,
+                "the CPU counter is not invariant, so asm timings may vary with CPU frequency"
+            ),
+            Self::Wraparound => write!(
+                fmt,
+                "the timer reading at stop was before that at start, so the counter has wrapped"
+            ),
+        }
+    }
+}
+
+//ip Error for TimerError
+impl core::error::Error for TimerError {}
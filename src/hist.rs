@@ -0,0 +1,379 @@
+//a Imports
+use crate::{BaseTimer, RecordDelta, TArch, TDesc};
+
+//a Useful functions
+//fp nearest_rank
+/// Return the *p* percentile (0 to 100) of some sorted samples, using
+/// the nearest-rank method; this is 0 if there are no samples
+pub(crate) fn nearest_rank(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let n = sorted.len();
+    let rank = ((p.clamp(0.0, 100.0) / 100.0) * (n as f64)).ceil() as usize;
+    sorted[rank.clamp(1, n) - 1]
+}
+
+//a HistTimer
+//tp HistTimer
+/// A [HistTimer] records the distribution of the times taken between
+/// 'start' and 'stop' as a histogram, without storing every sample
+///
+/// The histogram has *B* buckets; each bucket has a lower edge, and
+/// it counts the deltas that are no smaller than its edge, but
+/// smaller than the edge of the next bucket. The last bucket is
+/// open-ended; deltas smaller than the first edge are counted in the
+/// first bucket.
+///
+/// By default the edges are logarithmic - 0, 1, 2, 4, 8, and so on in
+/// powers of two. A HistTimer created with [HistTimer::log_scale] has
+/// logarithmic edges spread across a given range instead, and
+/// interpolates percentiles logarithmically within each bucket; one
+/// created with [HistTimer::with_edges] has explicit edges.
+///
+/// Alternatively a HistTimer created with [HistTimer::auto] stores its
+/// first *warmup* samples individually, and then sets the bucket edges
+/// from them (so that the samples are spread evenly across the
+/// buckets), re-binning them and continuing with bucketed recording;
+/// the range of the deltas then need not be known in advance.
+///
+/// ```
+/// # use cpu_timer::HistTimer;
+/// let mut t = HistTimer::<true, 32>::default();
+/// for _ in 0..100 {
+///     t.start();
+///     // do something!
+///     t.stop();
+/// }
+/// println!("The median time was about {} ticks", t.percentile(50.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HistTimer<const S: bool, const B: usize>
+where
+    TDesc<S>: TArch,
+{
+    base: BaseTimer<S>,
+    edges: [u64; B],
+    counts: [u64; B],
+    min: u64,
+    max: u64,
+    warmup: usize,
+    auto_warmup: usize,
+    pending: Vec<u64>,
+    log_interpolate: bool,
+}
+
+//ip Default for HistTimer
+impl<const S: bool, const B: usize> std::default::Default for HistTimer<S, B>
+where
+    TDesc<S>: TArch,
+{
+    fn default() -> Self {
+        let mut edges = [0; B];
+        for (i, e) in edges.iter_mut().enumerate().skip(1) {
+            *e = 1_u64.checked_shl((i - 1) as u32).unwrap_or(u64::MAX);
+        }
+        Self {
+            base: BaseTimer::default(),
+            edges,
+            counts: [0; B],
+            min: u64::MAX,
+            max: 0,
+            warmup: 0,
+            auto_warmup: 0,
+            pending: vec![],
+            log_interpolate: false,
+        }
+    }
+}
+
+//ip HistTimer
+impl<const S: bool, const B: usize> HistTimer<S, B>
+where
+    TDesc<S>: TArch,
+{
+    //cp auto
+    /// Create a new HistTimer that records its first *warmup* samples
+    /// individually, and then sets its bucket edges at quantiles of
+    /// those samples
+    ///
+    /// ```
+    /// # use cpu_timer::HistTimer;
+    /// let mut t = HistTimer::<true, 16>::auto(100);
+    /// for _ in 0..1000 {
+    ///     t.start();
+    ///     // do something!
+    ///     t.stop();
+    /// }
+    /// assert!(!t.is_warming_up());
+    /// ```
+    pub fn auto(warmup: usize) -> Self {
+        Self {
+            warmup,
+            auto_warmup: warmup,
+            ..Self::default()
+        }
+    }
+
+    //cp with_edges
+    /// Create a new HistTimer with the given bucket edges
+    ///
+    /// Each edge is the (inclusive) lower edge of a bucket, and the
+    /// edges must be increasing; the last bucket is open-ended, so *B*
+    /// edges give *B* buckets. Deltas below the first edge are counted
+    /// in the first bucket.
+    ///
+    /// ```
+    /// # use cpu_timer::HistTimer;
+    /// let mut t = HistTimer::<true, 4>::with_edges([0, 100, 1000, 10_000]);
+    /// t.record(50);
+    /// t.record(150);
+    /// t.record(20_000);
+    /// assert_eq!(t.buckets(), &[1, 1, 0, 1]);
+    /// ```
+    pub fn with_edges(edges: [u64; B]) -> Self {
+        debug_assert!(
+            edges.windows(2).all(|w| w[0] < w[1]),
+            "HistTimer edges must be increasing"
+        );
+        Self {
+            edges,
+            ..Self::default()
+        }
+    }
+
+    //cp log_scale
+    /// Create a new HistTimer with its *B* bucket edges on a
+    /// logarithmic scale from *min* to *max*
+    ///
+    /// The first edge is *min* (or 1 if *min* is 0), and the last is
+    /// *max*; each edge is then a constant ratio of the previous (but
+    /// always at least one more than it). This gives the same relative
+    /// resolution for deltas of a few ticks as for millions of ticks.
+    /// With a single bucket its edge is *min*.
+    ///
+    /// ```
+    /// # use cpu_timer::HistTimer;
+    /// let t = HistTimer::<true, 7>::log_scale(10, 10_000_000);
+    /// assert_eq!(t.edges(), &[10, 100, 1000, 10_000, 100_000, 1_000_000, 10_000_000]);
+    /// ```
+    pub fn log_scale(min: u64, max: u64) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        let ratio = (max as f64 / min as f64).powf(1.0 / (B.saturating_sub(1).max(1) as f64));
+        let mut edges = [0; B];
+        let mut last = 0_u64;
+        for (i, e) in edges.iter_mut().enumerate() {
+            let v = if i != 0 && i + 1 == B {
+                max
+            } else {
+                (min as f64 * ratio.powi(i as i32)).round() as u64
+            };
+            *e = if i == 0 {
+                v
+            } else {
+                v.max(last.saturating_add(1))
+            };
+            last = *e;
+        }
+        Self {
+            edges,
+            log_interpolate: true,
+            ..Self::default()
+        }
+    }
+
+    //mp clear
+    /// Clear the histogram counts (and any warmup samples), keeping the
+    /// bucket edges
+    ///
+    /// A HistTimer created with [HistTimer::auto] is returned to
+    /// warming up, so that its bucket edges are set afresh from its
+    /// next *warmup* samples
+    pub fn clear(&mut self) {
+        self.counts = [0; B];
+        self.min = u64::MAX;
+        self.max = 0;
+        self.pending.clear();
+        self.warmup = self.auto_warmup;
+    }
+
+    //ap is_warming_up
+    /// Return true if the HistTimer is still collecting warmup samples
+    /// to set its bucket edges from
+    pub fn is_warming_up(&self) -> bool {
+        self.warmup > 0
+    }
+
+    //mp start
+    /// Record the ticks on start to a region-to-time
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.base.start();
+    }
+
+    //mp stop
+    /// Record the delta since the last start in the histogram
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        let delta = self.base.elapsed();
+        self.record(delta);
+    }
+
+    //mp record
+    /// Record a delta (e.g. measured elsewhere) in the histogram
+    pub fn record(&mut self, delta: u64) {
+        if B == 0 {
+            return;
+        }
+        if self.warmup > 0 {
+            self.pending.push(delta);
+            if self.pending.len() >= self.warmup {
+                self.set_edges_from_pending();
+            }
+            return;
+        }
+        self.record_bucketed(delta);
+    }
+
+    //mi set_edges_from_pending
+    /// Set the bucket edges at quantiles of the warmup samples, and
+    /// re-bin them
+    fn set_edges_from_pending(&mut self) {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.sort_unstable();
+        let n = pending.len();
+        for (i, e) in self.edges.iter_mut().enumerate() {
+            *e = pending[(i * n) / B];
+        }
+        self.warmup = 0;
+        for delta in pending {
+            self.record_bucketed(delta);
+        }
+    }
+
+    //mi record_bucketed
+    /// Record a delta in the appropriate bucket
+    fn record_bucketed(&mut self, delta: u64) {
+        let i = self
+            .edges
+            .partition_point(|e| *e <= delta)
+            .saturating_sub(1);
+        self.counts[i] = self.counts[i].saturating_add(1);
+        self.min = self.min.min(delta);
+        self.max = self.max.max(delta);
+    }
+
+    //ap edges
+    /// Return the lower edges of the buckets
+    pub fn edges(&self) -> &[u64; B] {
+        &self.edges
+    }
+
+    //ap buckets
+    /// Return the counts of the buckets
+    pub fn buckets(&self) -> &[u64; B] {
+        &self.counts
+    }
+
+    //ap total
+    /// Return the total number of deltas recorded
+    pub fn total(&self) -> u64 {
+        if self.is_warming_up() {
+            return self.pending.len() as u64;
+        }
+        self.counts.iter().fold(0, |acc, c| acc.saturating_add(*c))
+    }
+
+    //mi bucket_range
+    /// Return the range of delta values covered by a bucket, using the
+    /// smallest and largest deltas recorded for the open ends
+    fn bucket_range(&self, i: usize) -> (u64, u64) {
+        let lo = if i == 0 {
+            self.edges[0].min(self.min)
+        } else {
+            self.edges[i]
+        };
+        let hi = if i + 1 < B {
+            self.edges[i + 1]
+        } else {
+            self.max.saturating_add(1)
+        };
+        (lo, hi.max(lo))
+    }
+
+    //ap cdf
+    /// Return the cumulative distribution of the deltas recorded, as
+    /// the (exclusive) upper edge of each bucket and the fraction of
+    /// the deltas that are below that edge
+    ///
+    /// The upper edge of the last bucket is one more than the largest
+    /// delta recorded. This is empty if no deltas have been recorded
+    /// (or while warming up).
+    pub fn cdf(&self) -> Vec<(u64, f64)> {
+        let total = self.total();
+        if total == 0 || self.is_warming_up() {
+            return vec![];
+        }
+        let mut cum = 0_u64;
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                cum += c;
+                (self.bucket_range(i).1, cum as f64 / total as f64)
+            })
+            .collect()
+    }
+
+    //ap percentile
+    /// Return an estimate of the *p* percentile (0 to 100) of the
+    /// deltas recorded, interpolating within the bucket that contains it
+    ///
+    /// The interpolation is linear, except for a HistTimer created with
+    /// [HistTimer::log_scale], for which it is logarithmic (matching
+    /// the spacing of the edges)
+    ///
+    /// While warming up this is exact (using the nearest-rank method);
+    /// it is 0 if no deltas have been recorded
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.is_warming_up() {
+            let mut sorted = self.pending.clone();
+            sorted.sort_unstable();
+            return nearest_rank(&sorted, p);
+        }
+        let total = self.total();
+        if total == 0 {
+            return 0;
+        }
+        let target = (p.clamp(0.0, 100.0) / 100.0) * (total as f64);
+        let mut cum = 0_u64;
+        for (i, c) in self.counts.iter().enumerate() {
+            if *c == 0 {
+                continue;
+            }
+            if (cum + c) as f64 >= target {
+                let (lo, hi) = self.bucket_range(i);
+                let frac = ((target - cum as f64) / (*c as f64)).clamp(0.0, 1.0);
+                let v = if self.log_interpolate && lo > 0 {
+                    lo as f64 * (hi as f64 / lo as f64).powf(frac)
+                } else {
+                    lo as f64 + frac * ((hi - lo) as f64)
+                };
+                return (v as u64).clamp(self.min, self.max);
+            }
+            cum += c;
+        }
+        self.max
+    }
+}
+
+//ip RecordDelta for HistTimer
+impl<const S: bool, const B: usize> RecordDelta<S> for HistTimer<S, B>
+where
+    TDesc<S>: TArch,
+{
+    fn record(&mut self, delta: u64) {
+        HistTimer::record(self, delta);
+    }
+}
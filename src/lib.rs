@@ -23,21 +23,102 @@
 //!
 //! The library does not attempt to take into account any overheads of
 //! using the timers; that is for the user. Normally the overheads
-//! will be small compared to the times being measured.
+//! will be small compared to the times being measured; for very short
+//! regions [Timer::measure_overhead] measures the overhead, which can
+//! then be subtracted with [DeltaTimer::value_compensated] (or
+//! [AccTimer::set_overhead]).
+//!
+//! The `overhead` example (`cargo run --release --example overhead`)
+//! measures the per-read overhead of the backends on the current
+//! machine, together with that of the different fence/barrier
+//! variants of the raw CPU counter read.
 //!
 //! # CPU support (for non-experimental Rustc target architectures)
 //!
 //! For the stable Rustc-supported architectures, CPU implementations
 //! are provided for:
 //!
-//! - [ ] x86    
+//! - [x] x86
 //! - [x] x86_64
 //! - [x] aarch64
-//! - [ ] wasm32
+//! - [x] riscv32 and riscv64 (but see the `riscv-rdtime` feature)
+//! - [ ] wasm32 (but see the `wasm-perf` feature)
 //!
 //! Nonsupported architectures resort to the [std::time::Instant]
 //! 'now' method instead (which can be perfectly adequate)
 //!
+//! Where an environment may not support the CPU counter properly
+//! (for example, some virtualized environments), [probe_asm_timer]
+//! can be used to check it at runtime.
+//!
+//! A counter that the crate does not know about (such as a
+//! memory-mapped FPGA or driver timer) can be used by implementing
+//! [CustomCounter] for a type, and giving [CustomArch] of that type as
+//! the architecture of [Timer], [DeltaTimer], [AccTimer], [AccArray],
+//! [AccArray2D], [AccTrace] or [AccVec], such as `DeltaTimer<true, CustomArch<MyCounter>>`.
+//!
+//! On x86_64 Linux the `topology` module can be used to check that a
+//! thread did not migrate to a different physical core during a
+//! measurement (hyperthreads of one core share its counter).
+//!
+//! # Features
+//!
+//! - `std` (default): use the standard library. Without this the crate
+//!   is `no_std`, and provides just the asm timers (so only for x86,
+//!   x86_64, aarch64 and riscv) and the types that do not need allocation or
+//!   std::time, such as [Timer], [DeltaTimer], [AccTimer], [AccArray]
+//!   and [Trace]; the other features all require `std`
+//!
+//! - `cpu-time`: on Linux and MacOs use the CPU time consumed by the
+//!   calling thread, in nanoseconds, for the std::time implementation,
+//!   rather than wall clock time; time for which the thread is
+//!   descheduled is then not counted
+//!
+//! - `vdso`: on Linux call `clock_gettime(CLOCK_MONOTONIC)` (which is
+//!   implemented in the VDSO) directly for the std::time
+//!   implementation, rather than using `std::time::Instant`, to reduce
+//!   its overhead; `cpu-time` takes precedence if both are enabled
+//!
+//! - `x86-mfence`: on x86_64 precede the CPU counter read with `mfence`
+//!   and `lfence`, so that it is ordered after all preceding memory
+//!   operations are globally visible, at a higher overhead
+//!
+//! - `aarch64-pmccntr`: on aarch64 read the PMU cycle counter
+//!   `pmccntr_el0` rather than the virtual counter `cntvct_el0`, for
+//!   true CPU cycle counts; user (EL0) access to the PMU must have been
+//!   enabled at EL1 (as is possible on bare metal), otherwise the read
+//!   traps
+//!
+//! - `riscv-rdtime`: on riscv32 and riscv64 read the fixed-frequency
+//!   `time` CSR rather than the `cycle` CSR, for environments (such as
+//!   Linux from 6.6 by default) that trap userspace reads of `cycle`
+//!
+//! - `sequence`: record the sequence of indices accumulated by an
+//!   [AccVec] since its last start, so that tests can check that the
+//!   instrumentation was hit in the expected order
+//!
+//! - `metrics`: add `emit_metrics` methods to [AccArray] and [AccVec],
+//!   to export the accumulated values and counts to the `metrics`
+//!   crate
+//!
+//! - `csv`: add `write_csv` methods to [AccArray] and [AccVec], to
+//!   write the accumulated values, counts and averages as CSV (with a
+//!   header row), for spreadsheets and other analysis tools
+//!
+//! - `json`: add `to_json` methods to [AccArray] and [AccVec], to
+//!   render the accumulated values, counts and averages as a fixed
+//!   JSON summary, for dashboards and monitoring tools
+//!
+//! - `serde`: implement `Serialize` and `Deserialize` for [AccArray]
+//!   and [AccVec], for just the accumulated values and counts (a
+//!   deserialized accumulator has a fresh timer), and for a
+//!   [TimingReport], which also carries the labels of the entries
+//!
+//! - `wasm-perf`: on wasm32 (in a browser window) use
+//!   `performance.now()` for the CPU specific implementation, with
+//!   ticks of nanoseconds; `std::time::Instant` is not available in
+//!   all wasm environments
+//!
 //! # Types
 //!
 //! The types in the library are all generic on *UseAsm* whether the CPU
@@ -58,6 +139,16 @@
 //! the timer as well as returning the elapsed time, in a single
 //! operation.
 //!
+//! The `elapsed_unit` method returns the elapsed time typed by its
+//! unit: [Ticks] for the CPU specific implementation, and [Nanos] for
+//! std::time.
+//!
+//! ## DynTimer
+//!
+//! The [DynTimer] wraps either an asm or a std::time [DeltaTimer],
+//! selected at runtime, for code paths that cannot be generic on
+//! *UseAsm*.
+//!
 //! ## DeltaTimer
 //!
 //! The [DeltaTimer] allows for *recording* the delta in CPU ticks
@@ -79,6 +170,12 @@
 //! println!("That took {} nanoseconds", t.value());
 //! ```
 //!
+//! The `scope` method of a [DeltaTimer] starts it and returns a
+//! [ScopedTimer] guard, which stops the timer when it is dropped.
+//!
+//! A [DeltaTimer] can be paused and resumed between its start and
+//! stop, to exclude regions (such as sleeps or IO) from its value.
+//!
 //! ## AccTimer
 //!
 //! Frequently one will want to repeatedly time a piece of code, to
@@ -98,6 +195,21 @@
 //! println!("That took an average of {} ticks", t.acc_value()/100);
 //! ```
 //!
+//! An [AtomicAccTimer] is an accumulator that can be shared between
+//! threads (on targets with 64-bit atomics); its 'start' returns a
+//! token that is passed to its 'stop'.
+//!
+//! ## MinTimer
+//!
+//! The [MinTimer] keeps the minimum delta over many starts and stops,
+//! as the floor latency of an operation (the counterpart to the
+//! average of an [AccTimer]).
+//!
+//! ## PairTimer
+//!
+//! The [PairTimer] holds two named [DeltaTimer]s, and reports their
+//! values and ratio, for comparing two alternatives.
+//!
 //! ## AccArray
 //!
 //! An [AccArray] is used to accumulate timer values, storing not just
@@ -112,7 +224,7 @@
 //!    u64, u128, usize, f32, f64, or () to not accumulate times)
 //!
 //!  * C is the type used for counting occurrences (u8, u16, u32,
-//!     u64, u128, usize, f32, f64, or () to not count occurrences)
+//!    u64, u128, usize, f32, f64, or () to not count occurrences)
 //!
 //!  * N can be any usize; the space for the occurrence accumulators
 //!    and counters is statically held within the type, so *N* effects
@@ -125,6 +237,12 @@
 //! elapsed since the last start is accumulated and the occurrences
 //! counted.
 //!
+//! ## AccArray2D
+//!
+//! An [AccArray2D] is a two-dimensional [AccArray], indexed by a row
+//! and a column, for regions that are naturally two-dimensional (such
+//! as compiler passes and the kinds of node they process).
+//!
 //! ## AccVec
 //!
 //! An [AccVec] is a less static version of [AccArray], using an array
@@ -152,6 +270,9 @@
 //! The trace will have three entries, which are the delta times for
 //! the three operations.
 //!
+//! A [NamedTrace] is a [Trace] with a label for each step, which are
+//! shown when it is displayed.
+//!
 //! ## AccTrace
 //!
 //! The [AccTrace] accumulates a number of iterations of a Trace;
@@ -193,6 +314,46 @@
 //! The trace will have four entries, which are the accumulated delta times for
 //! the four complex things.
 //!
+//! ## HistTimer
+//!
+//! The [HistTimer] records the distribution of the time deltas between
+//! start and stop in a fixed number of buckets (logarithmic by
+//! default, or set with [HistTimer::with_edges]), from which
+//! percentiles can be estimated without storing every sample.
+//!
+//! ## AdaptiveTimer
+//!
+//! The [AdaptiveTimer] stores individual samples (for exact
+//! percentiles) until a threshold number have been recorded, and then
+//! switches to a histogram to bound its memory.
+//!
+//! ## SampleRing
+//!
+//! A [SampleRing] stores the last *N* time deltas between start and
+//! stop in a ring buffer, from which exact percentiles of the recent
+//! samples can be calculated in bounded memory.
+//!
+//! ## Measured
+//!
+//! A [Measured] guard reads the timer when it is created, and records
+//! the delta into a [RecordDelta] sink when it is dropped; the sink
+//! can be an [AccTimer], a [HistTimer], an [AdaptiveTimer], a
+//! [SampleRing], or one index of an [AccArray] (an [AccSlot]).
+//!
+//! ## Calibration
+//!
+//! A [Calibration] measures the number of CPU counter ticks per
+//! nanosecond against `std::time::Instant`; its `hz` method gives the
+//! effective tick frequency. Where the architecture provides the
+//! frequency of its counter (such as `cntfrq_el0` on aarch64)
+//! [counter_frequency] returns it directly.
+//!
+//! ## TimeWeightedAvg
+//!
+//! A [TimeWeightedAvg] records the average of a fluctuating quantity
+//! (such as a queue depth) over time, with each value weighted by the
+//! ticks for which it persisted.
+//!
 //! # OS-specific notes
 //!
 //! These outputs are generated from tests/cpu_timer.rs, test_timer_values
@@ -345,14 +506,51 @@
 // 100, 24560
 
 //a Imports
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(
+    feature = "std",
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+)))]
+compile_error!("the std feature is required for architectures without an asm timer");
+
 mod delta;
 mod traits;
 
+mod acc_array_2d;
 mod acc_vec;
+#[cfg(feature = "std")]
+mod adaptive;
+// The architecture specific timers are each a nested 'arch' module
+#[allow(clippy::module_inception)]
 mod arch;
+#[cfg(target_has_atomic = "64")]
+mod atomic;
 mod base;
+#[cfg(feature = "std")]
+mod calibrate;
+#[cfg(feature = "std")]
+mod dynamic;
+mod error;
+#[cfg(feature = "std")]
+mod hist;
+mod measured;
+pub mod raw;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod ring;
 mod timers;
+#[cfg(all(feature = "std", target_arch = "x86_64", target_os = "linux"))]
+pub mod topology;
 mod trace;
+mod units;
+mod utils;
+mod weighted;
 
 //a Export to the crate, but not outside
 pub(crate) use base::BaseTimer;
@@ -360,8 +558,32 @@ pub(crate) use delta::Delta;
 pub(crate) use traits::private;
 
 //a Export to outside
-pub use acc_vec::{AccArray, AccVec};
-pub use arch::TDesc;
-pub use timers::{AccTimer, DeltaTimer, Timer};
-pub use trace::{AccTrace, Trace};
-pub use traits::{TArch, TraceCount, TraceValue};
+pub use acc_array_2d::AccArray2D;
+#[cfg(feature = "std")]
+pub use acc_vec::AccVec;
+pub use acc_vec::{rate, tick_rate, AccArray, AccGuard, AccSlot, AccStat};
+#[cfg(feature = "std")]
+pub use adaptive::AdaptiveTimer;
+#[cfg(feature = "std")]
+pub use arch::probe_asm_timer;
+pub use arch::{counter_frequency, tsc_is_invariant, uses_asm, CustomArch, TDesc};
+#[cfg(target_has_atomic = "64")]
+pub use atomic::AtomicAccTimer;
+pub use base::{start_all, start_all_arch};
+#[cfg(feature = "std")]
+pub use calibrate::Calibration;
+#[cfg(feature = "std")]
+pub use dynamic::DynTimer;
+pub use error::TimerError;
+#[cfg(feature = "std")]
+pub use hist::HistTimer;
+pub use measured::Measured;
+#[cfg(feature = "std")]
+pub use report::{TimingEntry, TimingReport};
+#[cfg(feature = "std")]
+pub use ring::SampleRing;
+pub use timers::{AccTimer, DeltaTimer, MinTimer, PairTimer, ScopedTimer, Timer};
+pub use trace::{anchor, AccTrace, NamedTrace, Trace};
+pub use traits::{CustomCounter, RecordDelta, Startable, TArch, TimerUnit, TraceCount, TraceValue};
+pub use units::{Nanos, Ticks};
+pub use weighted::TimeWeightedAvg;
@@ -0,0 +1,72 @@
+//a Imports
+use crate::{BaseTimer, RecordDelta, TArch, TDesc};
+
+//a Measured
+//tp Measured
+/// A guard that reads the timer when it is created, and records the
+/// delta to when it is dropped into a [RecordDelta] sink
+///
+/// The sink may be a mutable reference to any [RecordDelta], so the
+/// same measurement code can record into an [crate::AccTimer], a
+/// [crate::HistTimer], an [crate::AccSlot] of an [crate::AccArray],
+/// and so on.
+///
+/// ```
+/// # use cpu_timer::{AccArray, AccTimer, HistTimer, Measured};
+/// let mut t = AccTimer::<true>::default();
+/// let mut h = HistTimer::<true, 16>::default();
+/// let mut a = AccArray::<true, u64, u32, 4>::default();
+/// {
+///     let _m = Measured::into(&mut t);
+///     // do something!
+/// }
+/// {
+///     let _m = Measured::into(&mut h);
+///     // do something!
+/// }
+/// {
+///     let _m = Measured::into(a.slot(2));
+///     // do something!
+/// }
+/// assert_eq!(t.count(), 1);
+/// assert_eq!(h.total(), 1);
+/// assert_eq!(a.cnts()[2], 1);
+/// ```
+pub struct Measured<const S: bool, R>
+where
+    TDesc<S>: TArch,
+    R: RecordDelta<S>,
+{
+    start: u64,
+    sink: R,
+}
+
+//ip Measured
+impl<const S: bool, R> Measured<S, R>
+where
+    TDesc<S>: TArch,
+    R: RecordDelta<S>,
+{
+    //cp into
+    /// Read the timer, and return a guard that records the delta into
+    /// *sink* when it is dropped
+    #[inline(always)]
+    pub fn into(sink: R) -> Self {
+        Self {
+            start: BaseTimer::<S>::now_ticks(),
+            sink,
+        }
+    }
+}
+
+//ip Drop for Measured
+impl<const S: bool, R> core::ops::Drop for Measured<S, R>
+where
+    TDesc<S>: TArch,
+    R: RecordDelta<S>,
+{
+    fn drop(&mut self) {
+        let delta = BaseTimer::<S>::now_ticks().wrapping_sub(self.start);
+        self.sink.record(delta);
+    }
+}
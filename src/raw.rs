@@ -0,0 +1,35 @@
+//! Raw access to the timer value, for the lowest overhead timing
+//!
+//! Two readings can be taken with [now] and subtracted (with
+//! `wrapping_sub`) to give the ticks between them, without any timer
+//! structure.
+
+//a Imports
+use crate::private;
+use crate::traits::private::Value;
+use crate::{TArch, TDesc};
+
+//a Functions
+//fp now
+/// Return the current value of the timer, as a u64 tick count
+///
+/// For the assembler timer (*S* true) this is the raw CPU counter.
+/// For a std::time timer (*S* false, or an architecture without an
+/// assembler timer) this is nanoseconds since an arbitrary (per
+/// process) epoch. In either case the value is only meaningful as a
+/// difference from another reading.
+///
+/// ```
+/// # use cpu_timer::raw;
+/// let t0 = raw::now::<true>();
+/// // do something!
+/// let t1 = raw::now::<true>();
+/// println!("That took {} ticks", t1.wrapping_sub(t0));
+/// ```
+#[inline(always)]
+pub fn now<const S: bool>() -> u64
+where
+    TDesc<S>: TArch,
+{
+    <TDesc<S> as private::ArchDesc>::get_timer().ticks()
+}
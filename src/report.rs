@@ -0,0 +1,83 @@
+//a TimingEntry
+//tp TimingEntry
+/// One labelled entry of a [TimingReport]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimingEntry {
+    /// The label of the entry, or its index if it has no label
+    pub label: String,
+    /// The accumulated value, in ticks
+    pub value: u64,
+    /// The number of occurrences accumulated
+    pub count: u64,
+}
+
+//a TimingReport
+//tp TimingReport
+/// A [TimingReport] is a self-describing export of the accumulated
+/// values and counts of an [AccArray] or [AccVec], together with the
+/// label of each entry
+///
+/// With the `serde` feature this can be serialized, so that a
+/// complete set of profiling results can be sent to another process
+/// and rendered there without a separate schema.
+///
+/// [AccArray]: crate::AccArray
+/// [AccVec]: crate::AccVec
+///
+/// ```
+/// # use cpu_timer::AccArray;
+/// let mut arr = AccArray::<true, u64, u32, 2>::default();
+/// arr.set_labels(&["parse", "emit"]);
+/// arr.start();
+/// arr.acc_n(1);
+/// let report = arr.export();
+/// assert_eq!(report.entries[1].label, "emit");
+/// assert_eq!(report.entries[1].count, 1);
+/// println!("{report}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimingReport {
+    /// The entries, in index order
+    pub entries: Vec<TimingEntry>,
+}
+
+//ip TimingReport
+impl TimingReport {
+    //mp push
+    /// Add an entry to the report
+    pub(crate) fn push(&mut self, label: String, value: u64, count: u64) {
+        self.entries.push(TimingEntry {
+            label,
+            value,
+            count,
+        });
+    }
+}
+
+//ip Display for TimingReport
+impl std::fmt::Display for TimingReport {
+    /// Display each entry on a separate line, as its label, value,
+    /// count and average
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        for (i, e) in self.entries.iter().enumerate() {
+            if i != 0 {
+                writeln!(fmt)?;
+            }
+            if e.count == 0 {
+                write!(fmt, "{}: ({}, 0, -)", e.label, e.value)?;
+            } else {
+                write!(
+                    fmt,
+                    "{}: ({}, {}, {})",
+                    e.label,
+                    e.value,
+                    e.count,
+                    e.value / e.count
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
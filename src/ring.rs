@@ -0,0 +1,132 @@
+//a Imports
+use crate::hist::nearest_rank;
+use crate::{BaseTimer, RecordDelta, TArch, TDesc};
+
+//a SampleRing
+//tp SampleRing
+/// A [SampleRing] records the times taken between 'start' and 'stop'
+/// in a ring buffer of the last *N* samples
+///
+/// This is bounded in memory, and percentiles of the samples are exact
+/// (unlike those of a [HistTimer]), so it is useful for capturing the
+/// tail behavior of a region around an anomaly.
+///
+/// [HistTimer]: crate::HistTimer
+///
+/// ```
+/// # use cpu_timer::SampleRing;
+/// let mut r = SampleRing::<true, 64>::default();
+/// for _ in 0..100 {
+///     r.start();
+///     // do something!
+///     r.stop();
+/// }
+/// assert_eq!(r.samples().count(), 64);
+/// let p = r.percentiles(&[50.0, 99.0]);
+/// println!("Over the last 64 samples, median {} p99 {}", p[0], p[1]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SampleRing<const S: bool, const N: usize>
+where
+    TDesc<S>: TArch,
+{
+    base: BaseTimer<S>,
+    samples: [u64; N],
+    next: usize,
+    len: usize,
+}
+
+//ip Default for SampleRing
+impl<const S: bool, const N: usize> std::default::Default for SampleRing<S, N>
+where
+    TDesc<S>: TArch,
+{
+    fn default() -> Self {
+        Self {
+            base: BaseTimer::default(),
+            samples: [0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+}
+
+//ip SampleRing
+impl<const S: bool, const N: usize> SampleRing<S, N>
+where
+    TDesc<S>: TArch,
+{
+    //mp clear
+    /// Clear the recorded samples
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    //mp start
+    /// Record the ticks on start to a region-to-time
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.base.start();
+    }
+
+    //mp stop
+    /// Record the delta since the last start
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        let delta = self.base.elapsed();
+        self.record(delta);
+    }
+
+    //mp record
+    /// Record a delta (e.g. measured elsewhere), replacing the oldest
+    /// sample if the ring is full
+    pub fn record(&mut self, delta: u64) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.next] = delta;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    //ap len
+    /// Return the number of samples held, which is at most *N*
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    //ap is_empty
+    /// Return true if no samples are held
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    //ap samples
+    /// Return an iterator over the samples held, oldest first
+    pub fn samples(&self) -> impl Iterator<Item = u64> + '_ {
+        let first = (self.next + N - self.len) % N.max(1);
+        (0..self.len).map(move |i| self.samples[(first + i) % N])
+    }
+
+    //ap percentiles
+    /// Return the percentiles (each 0 to 100) of the samples held,
+    /// using the nearest-rank method on a sorted copy of the samples
+    ///
+    /// Each percentile is 0 if no samples are held
+    pub fn percentiles(&self, ps: &[f64]) -> Vec<u64> {
+        let mut sorted: Vec<u64> = self.samples().collect();
+        sorted.sort_unstable();
+        ps.iter().map(|p| nearest_rank(&sorted, *p)).collect()
+    }
+}
+
+//ip RecordDelta for SampleRing
+impl<const S: bool, const N: usize> RecordDelta<S> for SampleRing<S, N>
+where
+    TDesc<S>: TArch,
+{
+    fn record(&mut self, delta: u64) {
+        SampleRing::record(self, delta);
+    }
+}
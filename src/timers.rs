@@ -1,23 +1,15 @@
 //a Imports
-use crate::{BaseTimer, Delta, TArch, TDesc};
+use crate::{BaseTimer, Delta, RecordDelta, Startable, TArch, TDesc, TimerError};
 
 //a Timer
 //tp Timer
 /// A basic timer that supports start, elapsed, and elapsed_and_update
 #[derive(Default, Debug, Clone, Copy)]
-pub struct Timer<const S: bool>
-where
-    BaseTimer<S>: Default,
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct Timer<const S: bool, A: TArch = TDesc<S>> {
+    base: BaseTimer<S, A>,
 }
 
-impl<const S: bool> Timer<S>
-where
-    BaseTimer<S>: Default,
-    TDesc<S>: TArch,
-{
+impl<const S: bool, A: TArch> Timer<S, A> {
     //mp start
     /// Record the time now
     #[inline(always)]
@@ -32,12 +24,138 @@ where
         self.base.elapsed()
     }
 
+    //ap elapsed_unit
+    /// Return the time elapsed in the unit of the timer, i.e. [Ticks]
+    /// for an assembler timer or [Nanos] for a std::time timer
+    ///
+    /// [Ticks]: crate::Ticks
+    /// [Nanos]: crate::Nanos
+    #[inline(always)]
+    pub fn elapsed_unit(&self) -> <A as TArch>::Unit {
+        self.base.elapsed().into()
+    }
+
+    //ap elapsed_u32_saturating
+    /// Return the time elapsed as a u32, saturating at `u32::MAX`
+    /// rather than truncating, for storing short deltas compactly
+    #[inline(always)]
+    pub fn elapsed_u32_saturating(&self) -> u32 {
+        u32::try_from(self.base.elapsed()).unwrap_or(u32::MAX)
+    }
+
+    //ap elapsed_f64
+    /// Return the time elapsed as an f64, for use in floating point
+    /// statistics
+    #[inline(always)]
+    pub fn elapsed_f64(&self) -> f64 {
+        self.base.elapsed() as f64
+    }
+
     //mp elapsed_and_update
     /// Return the time elapsed as a u64, and update the timer
     #[inline(always)]
     pub fn elapsed_and_update(&mut self) -> u64 {
         self.base.elapsed_and_update()
     }
+
+    //fp measure_overhead
+    /// Measure the overhead of the timer, in ticks, as the median of
+    /// *iterations* back-to-back `start` and `elapsed` pairs with
+    /// nothing between them
+    ///
+    /// This may be subtracted from the values of short regions, such
+    /// as with [DeltaTimer::value_compensated] or
+    /// [AccTimer::set_overhead]; it is 0 if *iterations* is 0
+    ///
+    /// ```
+    /// # use cpu_timer::{DeltaTimer, Timer};
+    /// let overhead = Timer::<true>::measure_overhead(1000);
+    /// let mut t = DeltaTimer::<true>::default();
+    /// t.start();
+    /// // do something short!
+    /// t.stop();
+    /// println!("That took {} ticks", t.value_compensated(overhead));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn measure_overhead(iterations: usize) -> u64 {
+        let mut t = Self::default();
+        let mut deltas: Vec<u64> = (0..iterations)
+            .map(|_| {
+                t.start();
+                t.elapsed()
+            })
+            .collect();
+        deltas.sort_unstable();
+        crate::hist::nearest_rank(&deltas, 50.0)
+    }
+}
+
+//ip Timer<false>
+/// The std::time implementation has ticks of nanoseconds, so the time
+/// elapsed can be a Duration; this is not provided for the assembler
+/// timer, whose ticks are not a unit of time
+#[cfg(feature = "std")]
+impl Timer<false> {
+    //ap elapsed_duration
+    /// Return the time elapsed as a Duration
+    ///
+    /// ```
+    /// # use cpu_timer::Timer;
+    /// let mut t = Timer::<false>::default();
+    /// t.start();
+    /// // do something!
+    /// println!("That took {:?}", t.elapsed_duration());
+    /// ```
+    #[inline(always)]
+    pub fn elapsed_duration(&self) -> core::time::Duration {
+        core::time::Duration::from_nanos(self.elapsed())
+    }
+}
+
+//ip Timer<true>
+impl Timer<true> {
+    //cp new
+    /// Create a new timer in a const context (such as for a `static`)
+    ///
+    /// This is only available on architectures with an assembler
+    /// timer; the start is 0 until the first `start`
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    pub const fn new() -> Self {
+        Self {
+            base: BaseTimer::new(),
+        }
+    }
+
+    //mp set_start
+    /// Set the start of the timer to a previously captured tick value
+    /// (such as from [anchor]), so that subsequent elapsed times are
+    /// measured from that point rather than from the last start
+    ///
+    /// This is only meaningful for the assembler timer, whose ticks
+    /// are the raw CPU counter value; for a std::time timer the ticks
+    /// are relative to a per-process epoch, and so this is not
+    /// provided for `Timer<false>`
+    ///
+    /// [anchor]: crate::anchor
+    ///
+    /// ```
+    /// # use cpu_timer::{anchor, Timer};
+    /// let a = anchor::<true>();
+    /// // do something!
+    /// let mut t = Timer::<true>::default();
+    /// t.set_start(a);
+    /// println!("{} ticks since the anchor", t.elapsed());
+    /// ```
+    #[inline(always)]
+    pub fn set_start(&mut self, tick: u64) {
+        self.base.start_at(tick)
+    }
 }
 
 //a DeltaTimer
@@ -54,6 +172,14 @@ where
 /// the *value* method can then be used to retrieve the CPU ticks
 /// between the start and stop
 ///
+/// The timer may also be paused and resumed between the start and
+/// stop, to exclude regions (such as sleeps or IO) from the value; the
+/// value is then the total of the active segments
+///
+/// In a debug build 'stop' panics if the timer has not been started
+/// since it was constructed or cleared (as the delta would be
+/// meaningless); there is no such check in a release build
+///
 /// ```
 /// # use cpu_timer::DeltaTimer;
 /// let mut t = DeltaTimer::<true>::default();
@@ -63,20 +189,17 @@ where
 /// println!("That took {} ticks", t.value());
 /// ```
 #[derive(Default, Debug, Clone, Copy)]
-pub struct DeltaTimer<const S: bool>
-where
-    BaseTimer<S>: Default,
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct DeltaTimer<const S: bool, A: TArch = TDesc<S>> {
+    base: BaseTimer<S, A>,
     delta: Delta,
+    accumulated: Delta,
+    paused: bool,
+    #[cfg(debug_assertions)]
+    started: bool,
 }
 
 //ip DeltaTimer
-impl<const S: bool> DeltaTimer<S>
-where
-    TDesc<S>: TArch,
-{
+impl<const S: bool, A: TArch> DeltaTimer<S, A> {
     //mp clear
     /// Clear the timer and accumulated values
     pub fn clear(&mut self) {
@@ -88,20 +211,121 @@ where
     #[inline(always)]
     pub fn start(&mut self) {
         self.base.start();
+        self.accumulated = Delta::ZERO;
+        self.paused = false;
+        #[cfg(debug_assertions)]
+        {
+            self.started = true;
+        }
+    }
+
+    //mp pause
+    /// Pause the timer, accumulating the delta since the start (or the
+    /// last resume) as active time
+    ///
+    /// Pausing a paused timer has no effect
+    ///
+    /// ```
+    /// # use cpu_timer::DeltaTimer;
+    /// let mut t = DeltaTimer::<true>::default();
+    /// t.start();
+    /// // do something to time
+    /// t.pause();
+    /// // do something *not* to time, such as IO
+    /// t.resume();
+    /// // do something else to time
+    /// t.stop();
+    /// println!("The timed regions took {} ticks", t.value());
+    /// ```
+    #[inline(always)]
+    pub fn pause(&mut self) {
+        if !self.paused {
+            self.accumulated = self.accumulated.add(self.base.elapsed_delta());
+            self.paused = true;
+        }
+    }
+
+    //mp resume
+    /// Resume a paused timer, restarting the active segment from now
+    ///
+    /// Resuming a timer that is not paused restarts the current
+    /// segment, discarding the time since the start (or last resume)
+    #[inline(always)]
+    pub fn resume(&mut self) {
+        self.base.start();
+        self.paused = false;
+    }
+
+    //ap is_paused
+    /// Return true if the timer is paused
+    #[inline(always)]
+    pub fn is_paused(&self) -> bool {
+        self.paused
     }
 
     //mp delta
-    /// Return (without updating) the delta since start
+    /// Return (without updating) the delta since start (or the last
+    /// resume)
     #[inline(always)]
-    pub fn delta(&mut self) -> u64 {
+    pub fn delta(&self) -> u64 {
         self.base.elapsed_delta().into()
     }
 
+    //mp elapsed_and_update
+    /// Return the delta since start, and restart the timer from now
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn elapsed_and_update(&mut self) -> u64 {
+        self.base.elapsed_and_update()
+    }
+
     //mp stop
-    /// Record the delta time since the last start
+    /// Record the delta time since the last start, excluding any time
+    /// for which the timer was paused
     #[inline(always)]
     pub fn stop(&mut self) {
-        self.delta = self.base.elapsed_delta();
+        #[cfg(debug_assertions)]
+        debug_assert!(
+            self.started,
+            "DeltaTimer stopped without having been started"
+        );
+        self.delta = if self.paused {
+            self.accumulated
+        } else {
+            self.accumulated.add(self.base.elapsed_delta())
+        };
+    }
+
+    //mp stop_checked
+    /// Record the delta time since the last start, returning it, or an
+    /// error if the timer reading is before that at the start
+    ///
+    /// The plain `stop` wraps in that case, giving a huge delta; this
+    /// is for callers that need to reject such a value. On an error the
+    /// recorded delta is unchanged.
+    ///
+    /// ```
+    /// # use cpu_timer::DeltaTimer;
+    /// let mut t = DeltaTimer::<true>::default();
+    /// t.start();
+    /// // do something!
+    /// let ticks = t.stop_checked().unwrap();
+    /// assert_eq!(ticks, t.value());
+    /// ```
+    #[inline(always)]
+    pub fn stop_checked(&mut self) -> Result<u64, TimerError> {
+        #[cfg(debug_assertions)]
+        debug_assert!(
+            self.started,
+            "DeltaTimer stopped without having been started"
+        );
+        let segment = if self.paused {
+            0
+        } else {
+            self.base.elapsed_checked().ok_or(TimerError::Wraparound)?
+        };
+        self.delta = self.accumulated.add(segment.into());
+        Ok(self.delta.into())
     }
 
     //mp value
@@ -110,31 +334,293 @@ where
     pub fn value(&self) -> u64 {
         self.delta.into()
     }
+
+    //mp value_unit
+    /// Return the delta time in the unit of the timer, i.e. [Ticks]
+    /// for an assembler timer or [Nanos] for a std::time timer
+    ///
+    /// [Ticks]: crate::Ticks
+    /// [Nanos]: crate::Nanos
+    #[inline(always)]
+    pub fn value_unit(&self) -> <A as TArch>::Unit {
+        self.value().into()
+    }
+
+    //ap value_compensated
+    /// Return the delta time in ticks less an overhead (such as from
+    /// [Timer::measure_overhead]), saturating at zero
+    #[inline(always)]
+    pub fn value_compensated(&self, overhead: u64) -> u64 {
+        self.value().saturating_sub(overhead)
+    }
+
+    //mp scope
+    /// Start the timer, and return a guard that stops it when it is
+    /// dropped
+    ///
+    /// The guard holds a mutable borrow of the timer, so the value can
+    /// be read once the guard has dropped; the timer is stopped however
+    /// the scope is exited, including early returns (such as with `?`)
+    ///
+    /// ```
+    /// # use cpu_timer::DeltaTimer;
+    /// let mut t = DeltaTimer::<true>::default();
+    /// {
+    ///     let _g = t.scope();
+    ///     // do something!
+    /// }
+    /// println!("That took {} ticks", t.value());
+    /// ```
+    pub fn scope(&mut self) -> ScopedTimer<'_, S, A> {
+        self.start();
+        ScopedTimer { timer: self }
+    }
+}
+
+//ip Startable for DeltaTimer
+impl<const S: bool, A: TArch> Startable<S, A> for DeltaTimer<S, A> {
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+        self.accumulated = Delta::ZERO;
+        self.paused = false;
+        #[cfg(debug_assertions)]
+        {
+            self.started = true;
+        }
+    }
+}
+
+//tp ScopedTimer
+/// A guard returned by [DeltaTimer::scope], which stops the
+/// [DeltaTimer] when it is dropped
+pub struct ScopedTimer<'a, const S: bool, A: TArch = TDesc<S>> {
+    timer: &'a mut DeltaTimer<S, A>,
+}
+
+//ip Drop for ScopedTimer
+impl<const S: bool, A: TArch> core::ops::Drop for ScopedTimer<'_, S, A> {
+    fn drop(&mut self) {
+        self.timer.stop();
+    }
+}
+
+//ip DeltaTimer<true>
+impl DeltaTimer<true>
+where
+    TDesc<true>: TArch,
+{
+    //cp new
+    /// Create a new timer in a const context (such as for a `static`)
+    ///
+    /// This is only available on architectures with an assembler
+    /// timer; the start is 0 until the first `start`
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    pub const fn new() -> Self {
+        Self {
+            base: BaseTimer::new(),
+            delta: Delta::ZERO,
+            accumulated: Delta::ZERO,
+            paused: false,
+            #[cfg(debug_assertions)]
+            started: false,
+        }
+    }
+
+    //cp try_new
+    /// Create a new timer, returning an error if the asm timer is not
+    /// reliable on this machine (see [crate::tsc_is_invariant]), so
+    /// that the application can fall back to `DeltaTimer<false>`
+    ///
+    /// On architectures where this does not apply it always succeeds
+    pub fn try_new() -> Result<Self, TimerError> {
+        if crate::uses_asm::<true>() && !crate::tsc_is_invariant() {
+            Err(TimerError::NonInvariantCounter)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    //ap value_ticks
+    /// Return the delta time in CPU counter ticks
+    ///
+    /// This is the same as `value`; on an architecture without an asm
+    /// timer (see [crate::uses_asm]) the std::time implementation is
+    /// used, and the 'ticks' are then nanoseconds
+    #[inline(always)]
+    pub fn value_ticks(&self) -> u64 {
+        self.value()
+    }
+}
+
+//ip DeltaTimer<false>
+#[cfg(feature = "std")]
+impl DeltaTimer<false> {
+    //ap value_nanos
+    /// Return the delta time in nanoseconds
+    ///
+    /// This is the same as `value`
+    #[inline(always)]
+    pub fn value_nanos(&self) -> u64 {
+        self.value()
+    }
+
+    //ap elapsed_duration
+    /// Return (without updating) the time elapsed since start as a
+    /// Duration
+    #[inline(always)]
+    pub fn elapsed_duration(&self) -> core::time::Duration {
+        core::time::Duration::from_nanos(self.base.elapsed())
+    }
+
+    //ap within
+    /// Return true if the delta time is no more than *budget*
+    #[inline(always)]
+    pub fn within(&self, budget: core::time::Duration) -> bool {
+        self.exceeded_by(budget).is_none()
+    }
+
+    //ap exceeded_by
+    /// Return the amount by which the delta time exceeded *budget*, or
+    /// None if it is within the budget
+    #[inline(always)]
+    pub fn exceeded_by(&self, budget: core::time::Duration) -> Option<core::time::Duration> {
+        core::time::Duration::from_nanos(self.value_nanos())
+            .checked_sub(budget)
+            .filter(|d| !d.is_zero())
+    }
 }
 
 //a AccTimer
 //tp AccTimer
 /// An timer that accumulates the value for multiple timer start-stops
 ///
+/// The accumulated value and count saturate (at `u64::MAX`) rather
+/// than wrapping
+///
+/// An outlier threshold may be set, in which case any single delta
+/// exceeding the threshold (such as one including a scheduler
+/// interruption) is not accumulated, and is counted as rejected instead
+///
+/// A per-call overhead (such as that of the timer itself, measured with
+/// the `overhead` example) may also be set, in which case it is
+/// subtracted from each delta before it is accumulated; the Display
+/// then notes the overhead that was subtracted, as the accumulated
+/// values are no longer the raw measurements
+///
+/// An exponentially-weighted moving average of the accumulated deltas
+/// is also kept, so that recent deltas dominate (such as for an
+/// adaptive timeout); its weight *alpha* for each new delta is set
+/// with `with_alpha`, and is 1.0 (tracking the last delta) by default
 #[derive(Default, Debug, Clone, Copy)]
-pub struct AccTimer<const S: bool>
-where
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct AccTimer<const S: bool, A: TArch = TDesc<S>> {
+    base: BaseTimer<S, A>,
     delta: Delta,
     acc: Delta,
+    acc_sq: u128,
+    count: u64,
+    outlier_threshold: Option<u64>,
+    rejected: u64,
+    overhead: u64,
+    alpha: Option<f64>,
+    ewma: f64,
 }
 
 //ip AccTimer
-impl<const S: bool> AccTimer<S>
-where
-    TDesc<S>: TArch,
-{
+impl<const S: bool, A: TArch> AccTimer<S, A> {
+    //cp with_alpha
+    /// Create a new timer whose moving average weights each new delta
+    /// by *alpha* (and the previous average by 1 - *alpha*)
+    ///
+    /// An *alpha* close to 1.0 tracks the latest deltas closely; a
+    /// small *alpha* smooths over many deltas. It should be greater
+    /// than 0.0 and at most 1.0.
+    ///
+    /// ```
+    /// # use cpu_timer::AccTimer;
+    /// let mut t = AccTimer::<true>::with_alpha(0.1);
+    /// for _ in 0..10 {
+    ///     t.start();
+    ///     // do something!
+    ///     t.stop();
+    /// }
+    /// let timeout = 3.0 * t.ewma();
+    /// # assert!(timeout >= 0.0);
+    /// ```
+    pub fn with_alpha(alpha: f64) -> Self {
+        debug_assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "EWMA alpha {alpha} should be in (0, 1]"
+        );
+        Self {
+            alpha: Some(alpha),
+            ..Self::default()
+        }
+    }
+
     //mp clear
-    /// Clear the timer and accumulated values
+    /// Clear the timer and accumulated values, keeping any outlier
+    /// threshold, overhead and moving average weight
     pub fn clear(&mut self) {
-        *self = Self::default();
+        *self = Self {
+            outlier_threshold: self.outlier_threshold,
+            overhead: self.overhead,
+            alpha: self.alpha,
+            ..Self::default()
+        };
+    }
+
+    //ap alpha
+    /// Return the weight of each new delta in the moving average
+    pub fn alpha(&self) -> f64 {
+        self.alpha.unwrap_or(1.0)
+    }
+
+    //ap ewma
+    /// Return the exponentially-weighted moving average of the
+    /// accumulated deltas
+    ///
+    /// This starts at the first delta accumulated after a clear (and
+    /// is 0.0 before that); deltas rejected as outliers are not
+    /// included, and any overhead is subtracted, as for the
+    /// accumulated value
+    pub fn ewma(&self) -> f64 {
+        self.ewma
+    }
+
+    //mp set_outlier_threshold
+    /// Set the outlier threshold; deltas larger than this are then
+    /// not accumulated
+    pub fn set_outlier_threshold(&mut self, ticks: u64) {
+        self.outlier_threshold = Some(ticks);
+    }
+
+    //ap rejected_count
+    /// Return the number of deltas rejected as outliers since the last
+    /// clear
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected
+    }
+
+    //mp set_overhead
+    /// Set the per-call overhead in ticks, which is then subtracted
+    /// (saturating at zero) from each delta before it is accumulated
+    ///
+    /// The outlier threshold, if any, applies to the raw delta
+    pub fn set_overhead(&mut self, ticks: u64) {
+        self.overhead = ticks;
+    }
+
+    //ap overhead_applied
+    /// Return the per-call overhead in ticks that is subtracted from
+    /// each delta; this is 0 if the values are uncompensated
+    pub fn overhead_applied(&self) -> u64 {
+        self.overhead
     }
 
     //mp start
@@ -149,7 +635,133 @@ where
     #[inline(always)]
     pub fn stop(&mut self) {
         self.delta = self.base.elapsed_delta();
-        self.acc = self.acc.sat_add(self.delta);
+        self.accumulate_delta(self.overhead);
+    }
+
+    //mp stop_repeated
+    /// Record the ticks on stop from a region-to-time that contains
+    /// *reps* iterations of some code, and accumulate the delta per
+    /// iteration
+    ///
+    /// This measures code that is shorter than the granularity of the
+    /// timer: the delta for the whole loop is, after subtracting any
+    /// overhead, divided by *reps* (rounding to the nearest tick) and
+    /// that is accumulated (and counted once). The last delta and the
+    /// outlier threshold are also per iteration. A *reps* of 0 is
+    /// treated as 1.
+    ///
+    /// ```
+    /// # use cpu_timer::AccTimer;
+    /// let mut t = AccTimer::<true>::default();
+    /// let mut x = 0_u64;
+    /// t.start();
+    /// for i in 0..1000 {
+    ///     x = x.wrapping_mul(3).wrapping_add(i);
+    /// }
+    /// t.stop_repeated(1000);
+    /// println!("Each iteration took about {} ticks", t.last_delta());
+    /// # assert_eq!(t.count(), 1);
+    /// # core::hint::black_box(x);
+    /// ```
+    #[inline(always)]
+    pub fn stop_repeated(&mut self, reps: u64) {
+        let reps = reps.max(1);
+        let total = u64::from(self.base.elapsed_delta()).saturating_sub(self.overhead);
+        self.delta = (total.saturating_add(reps / 2) / reps).into();
+        self.accumulate_delta(0);
+    }
+
+    //mi accumulate_delta
+    /// Accumulate the last delta less an overhead, unless it is an
+    /// outlier (in which case it is counted as rejected); return true
+    /// if it was accumulated
+    #[inline(always)]
+    fn accumulate_delta(&mut self, overhead: u64) -> bool {
+        if self
+            .outlier_threshold
+            .is_some_and(|t| u64::from(self.delta) > t)
+        {
+            self.rejected = self.rejected.saturating_add(1);
+            false
+        } else {
+            let delta = u64::from(self.delta).saturating_sub(overhead);
+            self.ewma = if self.count == 0 {
+                delta as f64
+            } else {
+                let alpha = self.alpha();
+                alpha * (delta as f64) + (1.0 - alpha) * self.ewma
+            };
+            self.acc = self.acc.sat_add(delta.into());
+            self.acc_sq = self
+                .acc_sq
+                .saturating_add((delta as u128) * (delta as u128));
+            self.count = self.count.saturating_add(1);
+            true
+        }
+    }
+
+    //mp stop_if_below
+    /// Record the ticks on stop from a region-to-time, and update the
+    /// accumulator only if the delta is below *threshold*, returning
+    /// true if it was accumulated
+    ///
+    /// A delta that is not below the threshold (such as one including a
+    /// context switch) is counted as rejected instead, as is one that
+    /// exceeds any outlier threshold; this gives the 'fastest N%'
+    /// methodology of the crate documentation without keeping every
+    /// sample, once a threshold is known (e.g. from an earlier run).
+    ///
+    /// ```
+    /// # use cpu_timer::AccTimer;
+    /// let mut t = AccTimer::<true>::default();
+    /// t.start();
+    /// // do something!
+    /// if !t.stop_if_below(1_000_000) {
+    ///     println!("Rejected an outlier");
+    /// }
+    /// assert_eq!(t.count() + t.rejected_count(), 1);
+    /// ```
+    #[inline(always)]
+    pub fn stop_if_below(&mut self, threshold: u64) -> bool {
+        self.delta = self.base.elapsed_delta();
+        if u64::from(self.delta) >= threshold {
+            self.rejected = self.rejected.saturating_add(1);
+            return false;
+        }
+        self.accumulate_delta(self.overhead)
+    }
+
+    //mp time_ok
+    /// Time a fallible operation, accumulating the time taken only if
+    /// it succeeds, and return its result
+    ///
+    /// Failures are excluded as they may return early, and so skew the
+    /// statistics
+    ///
+    /// ```
+    /// # use cpu_timer::AccTimer;
+    /// let mut t = AccTimer::<true>::default();
+    /// let r: Result<u32, ()> = t.time_ok(|| Ok(1));
+    /// assert_eq!(r, Ok(1));
+    /// assert_eq!(t.count(), 1);
+    /// let r: Result<u32, ()> = t.time_ok(|| Err(()));
+    /// assert_eq!(r, Err(()));
+    /// assert_eq!(t.count(), 1);
+    /// ```
+    pub fn time_ok<T, E, F: FnOnce() -> Result<T, E>>(&mut self, f: F) -> Result<T, E> {
+        self.start();
+        let result = f();
+        if result.is_ok() {
+            self.stop();
+        }
+        result
+    }
+
+    //mp clear_last
+    /// Clear the last delta between start and stop to zero, without
+    /// changing the accumulated value
+    pub fn clear_last(&mut self) {
+        self.delta = Delta::ZERO;
     }
 
     //mp last_delta
@@ -165,4 +777,338 @@ where
     pub fn acc_value(&self) -> u64 {
         self.acc.into()
     }
+
+    //ap count
+    /// Return the number of deltas accumulated since the last clear
+    #[inline(always)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    //ap is_empty
+    /// Return true if nothing has been accumulated since the last
+    /// clear
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    //ap mean
+    /// Return the mean of the deltas accumulated since the last clear,
+    /// or 0 if there are none
+    pub fn mean(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            n => self.acc_value() as f64 / n as f64,
+        }
+    }
+
+    //ap variance
+    /// Return the (population) variance of the deltas accumulated
+    /// since the last clear, or 0 if there are none
+    ///
+    /// This uses the accumulated sum and sum of squares, which are
+    /// kept exactly (in u64 and u128 respectively) unless they
+    /// saturate
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let n = self.count as u128;
+        let acc = self.acc_value() as u128;
+        match n.checked_mul(self.acc_sq) {
+            Some(n_sq) => n_sq.saturating_sub(acc * acc) as f64 / (n * n) as f64,
+            None => {
+                let mean = self.mean();
+                (self.acc_sq as f64 / n as f64 - mean * mean).max(0.0)
+            }
+        }
+    }
+
+    //ap std_dev
+    /// Return the (population) standard deviation of the deltas
+    /// accumulated since the last clear, or 0 if there are none
+    #[cfg(feature = "std")]
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+}
+
+//ip AccTimer<true>
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+))]
+impl AccTimer<true> {
+    //cp new
+    /// Create a new timer in a const context (such as for a `static`)
+    ///
+    /// This is only available on architectures with an assembler
+    /// timer; the start is 0 until the first `start`
+    pub const fn new() -> Self {
+        Self {
+            base: BaseTimer::new(),
+            delta: Delta::ZERO,
+            acc: Delta::ZERO,
+            acc_sq: 0,
+            count: 0,
+            outlier_threshold: None,
+            rejected: 0,
+            overhead: 0,
+            alpha: None,
+            ewma: 0.0,
+        }
+    }
+}
+
+//ip Display for AccTimer
+impl<const S: bool, A: TArch> core::fmt::Display for AccTimer<S, A> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        let acc = self.acc_value();
+        if self.count == 0 {
+            write!(fmt, "({acc}, 0, -)")?;
+        } else {
+            write!(fmt, "({acc}, {}, {})", self.count, acc / self.count)?;
+        }
+        if self.overhead != 0 {
+            write!(fmt, " [overhead -{}/call]", self.overhead)?;
+        }
+        Ok(())
+    }
+}
+
+//ip AccTimer<false>
+#[cfg(feature = "std")]
+impl AccTimer<false> {
+    //ap acc_duration
+    /// Return the accumulated time as a Duration
+    pub fn acc_duration(&self) -> core::time::Duration {
+        core::time::Duration::from_nanos(self.acc_value())
+    }
+
+    //ap average_duration
+    /// Return the average time accumulated per delta as a Duration;
+    /// this is zero if nothing has been accumulated
+    pub fn average_duration(&self) -> core::time::Duration {
+        match self.count {
+            0 => core::time::Duration::ZERO,
+            n => core::time::Duration::from_nanos(self.acc_value() / n),
+        }
+    }
+}
+
+//ip RecordDelta for AccTimer
+/// A recorded delta is treated as the delta between a start and stop,
+/// subject to the outlier threshold and overhead
+impl<const S: bool, A: TArch> RecordDelta<S> for AccTimer<S, A> {
+    fn record(&mut self, delta: u64) {
+        self.delta = delta.into();
+        self.accumulate_delta(self.overhead);
+    }
+}
+
+//ip Startable for AccTimer
+impl<const S: bool, A: TArch> Startable<S, A> for AccTimer<S, A> {
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+    }
+}
+
+//a MinTimer
+//tp MinTimer
+/// A timer that keeps the minimum delta over multiple timer
+/// start-stops
+///
+/// The minimum is the cleanest estimate of the floor latency of an
+/// operation, as it is the delta least affected by interruptions,
+/// cache misses and so on; it is often what micro-benchmarks report,
+/// and is the counterpart to the average of an [AccTimer]
+///
+/// Until a delta has been recorded the minimum is `u64::MAX`
+///
+/// ```
+/// # use cpu_timer::MinTimer;
+/// let mut t = MinTimer::<true>::default();
+/// for _ in 0..100 {
+///     t.start();
+///     // do something!
+///     t.stop();
+/// }
+/// println!("That took at least {} ticks", t.min_value());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MinTimer<const S: bool>
+where
+    TDesc<S>: TArch,
+{
+    base: BaseTimer<S>,
+    min: u64,
+    count: u64,
+}
+
+//ip Default for MinTimer
+impl<const S: bool> Default for MinTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn default() -> Self {
+        Self {
+            base: BaseTimer::default(),
+            min: u64::MAX,
+            count: 0,
+        }
+    }
+}
+
+//ip MinTimer
+impl<const S: bool> MinTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    //mp clear
+    /// Clear the timer, and reset the minimum to `u64::MAX`
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    //mp start
+    /// Record the ticks on start to a region-to-time
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.base.start();
+    }
+
+    //mp stop
+    /// Record the ticks on stop from a region-to-time, and update the
+    /// minimum with the delta
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        let delta = self.base.elapsed();
+        self.record(delta);
+    }
+
+    //ap min_value
+    /// Return the minimum delta since the last clear, or `u64::MAX` if
+    /// there has been none
+    #[inline(always)]
+    pub fn min_value(&self) -> u64 {
+        self.min
+    }
+
+    //ap count
+    /// Return the number of deltas recorded since the last clear
+    #[inline(always)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+//ip RecordDelta for MinTimer
+impl<const S: bool> RecordDelta<S> for MinTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn record(&mut self, delta: u64) {
+        self.min = self.min.min(delta);
+        self.count = self.count.saturating_add(1);
+    }
+}
+
+//ip Startable for MinTimer
+impl<const S: bool> Startable<S> for MinTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+    }
+}
+
+//a PairTimer
+//tp PairTimer
+/// A pair of named [DeltaTimer]s, for comparing the times taken by two
+/// alternatives
+///
+/// ```
+/// # use cpu_timer::PairTimer;
+/// let mut p = PairTimer::<true>::new("before", "after");
+/// p.a().start();
+/// // do something the old way
+/// p.a().stop();
+/// p.b().start();
+/// // do something the new way
+/// p.b().stop();
+/// println!("{p}");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PairTimer<const S: bool>
+where
+    TDesc<S>: TArch,
+{
+    names: (&'static str, &'static str),
+    a: DeltaTimer<S>,
+    b: DeltaTimer<S>,
+}
+
+//ip PairTimer
+impl<const S: bool> PairTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    //cp new
+    /// Create a new pair of timers with the given names
+    pub fn new(name_a: &'static str, name_b: &'static str) -> Self {
+        Self {
+            names: (name_a, name_b),
+            a: DeltaTimer::default(),
+            b: DeltaTimer::default(),
+        }
+    }
+
+    //ap names
+    /// Return the names of the two timers
+    pub fn names(&self) -> (&'static str, &'static str) {
+        self.names
+    }
+
+    //mp a
+    /// Return the first timer
+    pub fn a(&mut self) -> &mut DeltaTimer<S> {
+        &mut self.a
+    }
+
+    //mp b
+    /// Return the second timer
+    pub fn b(&mut self) -> &mut DeltaTimer<S> {
+        &mut self.b
+    }
+
+    //ap compare
+    /// Return the values of the two timers, and the ratio of the
+    /// second to the first
+    ///
+    /// The ratio is infinite if the first value is zero (and NaN if
+    /// both are zero)
+    pub fn compare(&self) -> (u64, u64, f64) {
+        let a = self.a.value();
+        let b = self.b.value();
+        (a, b, b as f64 / a as f64)
+    }
+}
+
+//ip Display for PairTimer
+impl<const S: bool> core::fmt::Display for PairTimer<S>
+where
+    TDesc<S>: TArch,
+{
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        let (a, b, ratio) = self.compare();
+        write!(
+            fmt,
+            "{}: {a} ticks, {}: {b} ticks (ratio {ratio:.3})",
+            self.names.0, self.names.1
+        )
+    }
 }
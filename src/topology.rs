@@ -0,0 +1,139 @@
+//! CPU topology helpers for x86_64 Linux, to decide whether a thread
+//! migration between two logical processors kept the same physical
+//! core (and so the same TSC)
+//!
+//! The `rdtscp` instruction returns the TSC together with the
+//! IA32_TSC_AUX register, which Linux sets to the logical processor
+//! number (in the low 12 bits) and the NUMA node; sampling it at the
+//! start and stop of a region shows whether the thread migrated.
+//! Hyperthread siblings are the logical processors whose APIC ids
+//! differ only in the SMT bits, whose width is given by CPUID leaf
+//! 0xB; a migration between siblings is harmless.
+
+//a Imports
+use std::arch::x86_64::__cpuid_count;
+use std::sync::OnceLock;
+
+//a Functions
+//fp read_with_processor
+/// Read the CPU counter and the logical processor number with
+/// `rdtscp`, if the CPU supports it
+///
+/// ```
+/// # use cpu_timer::topology::{read_with_processor, same_core};
+/// if let Some((t0, p0)) = read_with_processor() {
+///     // do something!
+///     let (t1, p1) = read_with_processor().unwrap();
+///     if same_core(p0, p1) {
+///         println!("That took {} ticks", t1.wrapping_sub(t0));
+///     }
+/// }
+/// ```
+pub fn read_with_processor() -> Option<(u64, u32)> {
+    if !has_rdtscp() {
+        return None;
+    }
+    let lo: u64;
+    let hi: u64;
+    let aux: u32;
+    unsafe {
+        std::arch::asm!(
+            "rdtscp",
+            lateout("eax") lo,
+            lateout("edx") hi,
+            lateout("ecx") aux,
+            options(nomem, nostack)
+        );
+    }
+    Some((hi << 32 | lo, aux & 0xfff))
+}
+
+//fi has_rdtscp
+/// Return true if the CPU supports `rdtscp` (cached)
+fn has_rdtscp() -> bool {
+    static HAS_RDTSCP: OnceLock<bool> = OnceLock::new();
+    *HAS_RDTSCP.get_or_init(|| {
+        #[allow(unused_unsafe)]
+        let max_extended_leaf = unsafe { __cpuid_count(0x8000_0000, 0) }.eax;
+        #[allow(unused_unsafe)]
+        let edx = unsafe { __cpuid_count(0x8000_0001, 0) }.edx;
+        max_extended_leaf >= 0x8000_0001 && edx & (1 << 27) != 0
+    })
+}
+
+//fi smt_shift
+/// Return the number of low bits of an APIC id that identify the
+/// hyperthread within a core, from CPUID leaf 0xB (or 0 if that leaf is
+/// not supported, so that every logical processor is its own core)
+fn smt_shift() -> u32 {
+    #[allow(unused_unsafe)]
+    let max_leaf = unsafe { __cpuid_count(0, 0) }.eax;
+    if max_leaf < 0xb {
+        return 0;
+    }
+    #[allow(unused_unsafe)]
+    let leaf = unsafe { __cpuid_count(0xb, 0) };
+    // Level type 1 is SMT
+    if (leaf.ecx >> 8) & 0xff == 1 {
+        leaf.eax & 0x1f
+    } else {
+        0
+    }
+}
+
+//fi apic_ids
+/// Return the APIC id of each logical processor, indexed by processor
+/// number, from `/proc/cpuinfo` (read once)
+fn apic_ids() -> &'static [Option<u32>] {
+    static APIC_IDS: OnceLock<Vec<Option<u32>>> = OnceLock::new();
+    APIC_IDS.get_or_init(|| {
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let mut ids = vec![];
+        let mut processor = None;
+        for line in cpuinfo.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().parse::<u32>().ok();
+            match key.trim() {
+                "processor" => processor = value.map(|p| p as usize),
+                "apicid" => {
+                    if let (Some(p), Some(apic)) = (processor, value) {
+                        if ids.len() <= p {
+                            ids.resize(p + 1, None);
+                        }
+                        ids[p] = Some(apic);
+                    }
+                }
+                _ => (),
+            }
+        }
+        ids
+    })
+}
+
+//fp physical_core
+/// Return the physical core of a logical processor (as returned by
+/// [read_with_processor]), as its APIC id with the SMT bits removed
+///
+/// This is None if the APIC id of the processor is not known
+pub fn physical_core(processor: u32) -> Option<u32> {
+    static SMT_SHIFT: OnceLock<u32> = OnceLock::new();
+    let shift = *SMT_SHIFT.get_or_init(smt_shift);
+    apic_ids()
+        .get(processor as usize)
+        .copied()
+        .flatten()
+        .map(|apic| apic >> shift)
+}
+
+//fp same_core
+/// Return true if two logical processors (as returned by
+/// [read_with_processor]) are the same or are hyperthreads of the same
+/// physical core, and so share a TSC
+///
+/// If the physical core of either is not known then only the same
+/// processor is considered to be the same core
+pub fn same_core(a: u32, b: u32) -> bool {
+    a == b || physical_core(a).is_some_and(|c| physical_core(b) == Some(c))
+}
@@ -1,5 +1,38 @@
 //a Imports
-use crate::{BaseTimer, Delta, TArch, TDesc, TraceValue};
+use crate::utils::percentage;
+#[cfg(feature = "std")]
+use crate::AccVec;
+use crate::{BaseTimer, Delta, Startable, TArch, TDesc, TraceValue};
+
+//a Anchor
+//fp anchor
+/// Capture an anchor tick value, for aligning traces to a common epoch
+///
+/// An anchor should be captured at a known program point *before*
+/// the traces to be aligned are started; the `start_offset_from`
+/// method of each trace then returns the ticks from the anchor to the
+/// start of the trace, so that independently collected traces can be
+/// rendered against one zero.
+///
+/// For the std::time implementation the ticks are nanoseconds from
+/// an arbitrary (per process) epoch
+///
+/// ```
+/// # use cpu_timer::{anchor, Trace};
+/// let a = anchor::<true>();
+/// let mut t = Trace::<true, u32, 2>::default();
+/// t.start();
+/// t.next();
+/// t.next();
+/// println!("Trace started {} ticks after the anchor", t.start_offset_from(a));
+/// ```
+#[inline(always)]
+pub fn anchor<const S: bool>() -> u64
+where
+    TDesc<S>: TArch,
+{
+    BaseTimer::<S>::now_ticks()
+}
 
 //a Trace
 //tp Trace
@@ -13,41 +46,49 @@ use crate::{BaseTimer, Delta, TArch, TDesc, TraceValue};
 /// with the 'trace' method.
 ///
 /// A Trace can be generated for any N, for T in u8, u16, u32, u64, u128 and usize
+///
+/// The deltas are held internally at full (u64) precision, and are
+/// only narrowed to *T* when they are read, saturating at its
+/// maximum; so a step too long for *T* reads as the maximum, rather
+/// than being silently truncated.
 #[derive(Debug, Clone, Copy)]
-pub struct Trace<const S: bool, T: TraceValue, const N: usize>
-where
-    TDesc<S>: TArch,
-{
-    base: BaseTimer<S>,
+pub struct Trace<const S: bool, T: TraceValue, const N: usize, A: TArch = TDesc<S>> {
+    base: BaseTimer<S, A>,
     index: usize,
-    trace: [T; N],
+    trace: [Delta; N],
+    phantom: core::marker::PhantomData<T>,
 }
 
 //ip Default for Trace
-impl<const S: bool, T, const N: usize> std::default::Default for Trace<S, T, N>
+impl<const S: bool, T, const N: usize, A> core::default::Default for Trace<S, T, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
-    [T; N]: Default,
 {
     fn default() -> Self {
         let base = BaseTimer::default();
         let index = 0;
-        let trace = <[T; N]>::default();
-        Self { base, index, trace }
+        let trace = [Delta::ZERO; N];
+        let phantom = core::marker::PhantomData;
+        Self {
+            base,
+            index,
+            trace,
+            phantom,
+        }
     }
 }
 
 //ip Trace
-impl<const S: bool, T, const N: usize> Trace<S, T, N>
+impl<const S: bool, T, const N: usize, A> Trace<S, T, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
 {
     //mp clear
     /// Clear the timer and trace
     pub fn clear(&mut self) {
-        unsafe { *self = std::mem::zeroed() };
+        unsafe { *self = core::mem::zeroed() };
     }
 
     //mp start
@@ -71,55 +112,316 @@ where
     #[inline(always)]
     pub fn next(&mut self) {
         if self.index < N {
-            let delta = self.base.elapsed_delta_and_update();
-            self.trace[self.index] = delta.into();
+            self.trace[self.index] = self.base.elapsed_delta_and_update();
             self.index += 1;
         }
     }
 
+    //fi narrow
+    /// Narrow a delta to *T*, saturating at its maximum
+    #[inline(always)]
+    fn narrow(delta: Delta) -> T {
+        T::default().sat_add(delta.into())
+    }
+
+    //ap start_offset_from
+    /// Return the ticks from an anchor (captured with [anchor]) to the
+    /// last start of the trace
+    ///
+    /// This wraps if the trace was started before the anchor was captured
+    pub fn start_offset_from(&self, anchor: u64) -> u64 {
+        self.base.start_ticks().wrapping_sub(anchor)
+    }
+
     //mp trace
-    /// Return the current trace
-    pub fn trace(&self) -> &[T; N] {
-        &self.trace
+    /// Return the current trace, with each step narrowed to *T*
+    /// (saturating at its maximum)
+    pub fn trace(&self) -> [T; N] {
+        self.trace.map(Self::narrow)
+    }
+
+    //ap trace_labeled
+    /// Return the current trace, with each step paired with its label
+    #[cfg(feature = "std")]
+    pub fn trace_labeled<'a>(&self, labels: &[&'a str; N]) -> Vec<(&'a str, T)> {
+        labels.iter().copied().zip(self.trace()).collect()
+    }
+
+    //ap filled
+    /// Return an iterator over the steps of the trace that have been
+    /// recorded since the last start, each narrowed to *T* (saturating
+    /// at its maximum)
+    pub fn filled(&self) -> impl ExactSizeIterator<Item = T> + '_ {
+        self.trace[0..self.index].iter().map(|d| Self::narrow(*d))
+    }
+
+    //ap is_complete
+    /// Return true if all *N* steps have been recorded since the last
+    /// start
+    pub fn is_complete(&self) -> bool {
+        self.index == N
+    }
+
+    //mp verify
+    /// Check, in debug builds only, that all *N* steps have been
+    /// recorded since the last start
+    ///
+    /// A trace with fewer steps has trailing zeros that look like
+    /// genuine zero-tick steps, and usually indicates a missing call
+    /// to 'next'; this should be invoked before the trace is read to
+    /// catch that. In release builds this does nothing.
+    ///
+    /// A [Trace] is Copy, and so this cannot be done when it is dropped
+    #[inline(always)]
+    pub fn verify(&self) {
+        debug_assert!(
+            self.is_complete(),
+            "Trace has {} of {} steps recorded; is a call to 'next' missing?",
+            self.index,
+            N
+        );
+    }
+
+    //ap max_step
+    /// Return the index and delta of the slowest step recorded since
+    /// the last start (the first, if more than one step is equally
+    /// slow), or None if no steps have been recorded
+    ///
+    /// The delta is at full precision, and is not narrowed to *T*
+    pub fn max_step(&self) -> Option<(usize, u64)> {
+        let mut max: Option<(usize, u64)> = None;
+        for (i, d) in self.trace[0..self.index].iter().enumerate() {
+            let v: u64 = (*d).into();
+            if max.is_none_or(|(_, m)| v > m) {
+                max = Some((i, v));
+            }
+        }
+        max
+    }
+
+    //ap min_step
+    /// Return the index and delta of the fastest step recorded since
+    /// the last start (the first, if more than one step is equally
+    /// fast), or None if no steps have been recorded
+    ///
+    /// The delta is at full precision, and is not narrowed to *T*
+    pub fn min_step(&self) -> Option<(usize, u64)> {
+        let mut min: Option<(usize, u64)> = None;
+        for (i, d) in self.trace[0..self.index].iter().enumerate() {
+            let v: u64 = (*d).into();
+            if min.is_none_or(|(_, m)| v < m) {
+                min = Some((i, v));
+            }
+        }
+        min
+    }
+
+    //ap sparkline
+    /// Render the steps of the trace that have been recorded since the
+    /// last start as a string of unicode block characters, one per
+    /// step, scaled so that the largest step is a full block
+    ///
+    /// If all the steps are zero then every step is the lowest block
+    #[cfg(feature = "std")]
+    pub fn sparkline(&self) -> String {
+        const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = self.filled().map(|t| t.as_f64()).fold(0.0_f64, f64::max);
+        self.filled()
+            .map(|t| {
+                let level = if max > 0.0 {
+                    ((t.as_f64() / max) * 7.0).round() as usize
+                } else {
+                    0
+                };
+                BLOCKS[level.min(7)]
+            })
+            .collect()
+    }
+
+    //cp to_acc_vec
+    /// Create an [AccVec] containing the steps of the trace that have
+    /// been recorded since the last start, each with a count of 1
+    ///
+    /// The [AccVec] can then be used to continue collecting beyond *N*
+    /// steps, or merged with other dynamic data
+    #[cfg(feature = "std")]
+    pub fn to_acc_vec(&self) -> AccVec<S, T, u8, A> {
+        AccVec::from_acc_cnts(self.filled().map(|t| (t, 1)).collect())
     }
 }
 
-//a AccTrace
-//tp AccTrace
+//ip Startable for Trace
+impl<const S: bool, T, const N: usize, A> Startable<S, A> for Trace<S, T, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.base.start_at(ticks);
+        self.index = 0;
+    }
+}
+
+//a NamedTrace
+//tp NamedTrace
+/// A [NamedTrace] is a [Trace] with a label for each step, which are
+/// used when it is displayed
+///
+/// The labels are kept separately from the trace, so that 'next' is
+/// exactly as for a [Trace]
+///
+/// ```
+/// # use cpu_timer::NamedTrace;
+/// let mut t = NamedTrace::<true, u32, 3>::new(["parse", "check", "emit"]);
+/// t.start();
+/// // parse!
+/// t.next();
+/// // check!
+/// t.next();
+/// // emit!
+/// t.next();
+/// println!("{t}");
+/// ```
 #[derive(Debug, Clone, Copy)]
-pub struct AccTrace<const S: bool, T: TraceValue, const N: usize>
+pub struct NamedTrace<const S: bool, T: TraceValue, const N: usize>
 where
     TDesc<S>: TArch,
 {
     trace: Trace<S, T, N>,
+    labels: [&'static str; N],
+}
+
+//ip NamedTrace
+impl<const S: bool, T, const N: usize> NamedTrace<S, T, N>
+where
+    TDesc<S>: TArch,
+    T: TraceValue,
+{
+    //cp new
+    /// Create a new NamedTrace with a label for each step
+    pub fn new(labels: [&'static str; N]) -> Self {
+        Self {
+            trace: Trace::default(),
+            labels,
+        }
+    }
+
+    //mp start
+    /// Record the ticks on start to a region-to-time
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.trace.start();
+    }
+
+    //mp next
+    /// Record the delta for the next step, as for [Trace::next]
+    #[inline(always)]
+    pub fn next(&mut self) {
+        self.trace.next();
+    }
+
+    //ap labels
+    /// Return the labels of the steps
+    pub fn labels(&self) -> &[&'static str; N] {
+        &self.labels
+    }
+
+    //ap trace
+    /// Return the underlying trace
+    pub fn trace(&self) -> &Trace<S, T, N> {
+        &self.trace
+    }
+
+    //ap trace_labeled
+    /// Return the current trace, with each step paired with its label
+    #[cfg(feature = "std")]
+    pub fn trace_labeled(&self) -> Vec<(&'static str, T)> {
+        self.trace.trace_labeled(&self.labels)
+    }
+}
+
+//ip Display for NamedTrace
+impl<const S: bool, T, const N: usize> core::fmt::Display for NamedTrace<S, T, N>
+where
+    TDesc<S>: TArch,
+    T: TraceValue + core::fmt::Display,
+{
+    /// Display each step as its label and delta, such as
+    /// `parse: 12, check: 4, emit: 88`
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        for (i, (l, t)) in self.labels.iter().zip(self.trace.trace()).enumerate() {
+            if i != 0 {
+                write!(fmt, ", ")?;
+            }
+            write!(fmt, "{l}: {t}")?;
+        }
+        Ok(())
+    }
+}
+
+//ip Startable for NamedTrace
+impl<const S: bool, T, const N: usize> Startable<S> for NamedTrace<S, T, N>
+where
+    TDesc<S>: TArch,
+    T: TraceValue,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.trace.start_at(ticks);
+    }
+}
+
+//a AccTrace
+//tp AccTrace
+/// An [AccTrace] is a [Trace] whose traces can be accumulated, to
+/// find the time spent in each step across many iterations
+///
+/// The 'acc' method accumulates the current trace with saturating
+/// additions, so a step that would overflow *T* (as a u32 may, over
+/// millions of iterations) stays at its maximum; 'wrapping_acc'
+/// accumulates with wrapping additions instead, for callers that
+/// handle the wrap themselves (such as by just taking differences).
+#[derive(Debug, Clone, Copy)]
+pub struct AccTrace<const S: bool, T: TraceValue, const N: usize, A: TArch = TDesc<S>> {
+    trace: Trace<S, T, N, A>,
     acc: [T; N],
+    comps: [T::Comp; N],
+    iterations: u64,
 }
 
 //ip Default for AccTrace
-impl<const S: bool, T, const N: usize> std::default::Default for AccTrace<S, T, N>
+impl<const S: bool, T, const N: usize, A> core::default::Default for AccTrace<S, T, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
     [T; N]: Default,
 {
     fn default() -> Self {
         let trace = Trace::default();
         let acc = <[T; N]>::default();
-        Self { trace, acc }
+        let comps = [T::Comp::default(); N];
+        let iterations = 0;
+        Self {
+            trace,
+            acc,
+            comps,
+            iterations,
+        }
     }
 }
 
 //ip AccTrace
-impl<const S: bool, T, const N: usize> AccTrace<S, T, N>
+impl<const S: bool, T, const N: usize, A> AccTrace<S, T, N, A>
 where
-    TDesc<S>: TArch,
+    A: TArch,
     T: TraceValue,
 {
     //mp clear
     /// Clear the timer and accumulated values
     pub fn clear(&mut self) {
         self.trace.clear();
-        unsafe { self.acc = std::mem::zeroed() };
+        unsafe { self.acc = core::mem::zeroed() };
+        self.comps = [T::Comp::default(); N];
+        self.iterations = 0;
     }
 
     //mp start
@@ -137,18 +439,43 @@ where
     }
 
     //mp acc
-    /// Accumulate the current trace into the accumulated trace
+    /// Accumulate the current trace into the accumulated trace,
+    /// saturating at the maximum of *T*, and compensated for a float
+    /// *T*
     pub fn acc(&mut self) {
+        for i in 0..N {
+            self.acc[i] = self.acc[i].sat_add_comp(self.trace.trace[i].into(), &mut self.comps[i]);
+        }
+        self.iterations += 1;
+    }
+
+    //mp wrapping_acc
+    /// Accumulate the current trace into the accumulated trace,
+    /// wrapping on overflow of *T*
+    ///
+    /// Each step is narrowed to *T* (saturating) before it is added, as
+    /// for 'last_trace'
+    pub fn wrapping_acc(&mut self) {
         for i in 0..N {
             let v: Delta = self.acc[i].into();
-            let v = v.add(self.trace.trace[i].into());
+            let v = v.add(Trace::<S, T, N, A>::narrow(self.trace.trace[i]).into());
             self.acc[i] = v.into();
         }
+        self.iterations += 1;
+    }
+
+    //ap start_offset_from
+    /// Return the ticks from an anchor (captured with [anchor]) to the
+    /// start of the last trace
+    ///
+    /// This wraps if the trace was started before the anchor was captured
+    pub fn start_offset_from(&self, anchor: u64) -> u64 {
+        self.trace.start_offset_from(anchor)
     }
 
     //mp last_trace
-    /// Return the current trace
-    pub fn last_trace(&self) -> &[T; N] {
+    /// Return the current trace, with each step narrowed to *T*
+    pub fn last_trace(&self) -> [T; N] {
         self.trace.trace()
     }
 
@@ -157,4 +484,65 @@ where
     pub fn acc_trace(&self) -> &[T; N] {
         &self.acc
     }
+
+    //ap iterations
+    /// Return the number of traces accumulated since the last clear
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    //ap is_empty
+    /// Return true if no traces have been accumulated since the last
+    /// clear
+    pub fn is_empty(&self) -> bool {
+        self.iterations == 0
+    }
+
+    //ap slowest_step
+    /// Return the index and accumulated value of the step with the
+    /// largest accumulated time, if there are any steps
+    pub fn slowest_step(&self) -> Option<(usize, u64)> {
+        let mut slowest: Option<(usize, u64)> = None;
+        for (i, a) in self.acc.iter().enumerate() {
+            let v: Delta = (*a).into();
+            let v: u64 = v.into();
+            if slowest.is_none_or(|(_, s)| v > s) {
+                slowest = Some((i, v));
+            }
+        }
+        slowest
+    }
+
+    //ap slowest_step_avg
+    /// Return the index and average time per iteration of the step
+    /// with the largest accumulated time, if there are any steps and
+    /// any traces have been accumulated
+    pub fn slowest_step_avg(&self) -> Option<(usize, f64)> {
+        if self.iterations == 0 {
+            return None;
+        }
+        self.slowest_step()
+            .map(|(i, v)| (i, v as f64 / self.iterations as f64))
+    }
+
+    //ap as_percentages
+    /// Return the accumulated value of each step as a percentage of
+    /// the total accumulated across all the steps
+    ///
+    /// If nothing has been accumulated then every percentage is 0
+    pub fn as_percentages(&self) -> [f64; N] {
+        let total: f64 = self.acc.iter().map(|a| a.as_f64()).sum();
+        self.acc.map(|a| percentage(a.as_f64(), total))
+    }
+}
+
+//ip Startable for AccTrace
+impl<const S: bool, T, const N: usize, A> Startable<S, A> for AccTrace<S, T, N, A>
+where
+    A: TArch,
+    T: TraceValue,
+{
+    fn start_at(&mut self, ticks: u64) {
+        self.trace.start_at(ticks);
+    }
 }
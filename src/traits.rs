@@ -4,11 +4,21 @@
 /// implemented here only.
 pub(crate) mod private {
     //tp Value
-    pub(crate) trait Value: std::fmt::Debug + Default + Copy {
+    pub(crate) trait Value: core::fmt::Debug + Default + Copy {
         fn since(self, last: Self) -> crate::Delta;
         fn since_and_update(&mut self, now: Self) -> crate::Delta;
+        /// The value as a u64 tick count, from some arbitrary epoch
+        fn ticks(self) -> u64;
+        /// The value for a u64 tick count, the inverse of `ticks`
+        fn from_ticks(ticks: u64) -> Self;
     }
     impl Value for u64 {
+        fn ticks(self) -> u64 {
+            self
+        }
+        fn from_ticks(ticks: u64) -> Self {
+            ticks
+        }
         fn since(self, last: Self) -> crate::Delta {
             self.wrapping_sub(last).into()
         }
@@ -20,7 +30,7 @@ pub(crate) mod private {
     }
 
     //tp ArchDesc
-    pub(crate) trait ArchDesc: Default {
+    pub(crate) trait ArchDesc: core::fmt::Debug + Default + Copy {
         /// Value returned by the timer
         ///
         /// This is stored within timers but is not visible to users
@@ -31,11 +41,71 @@ pub(crate) mod private {
         fn get_timer() -> Self::Value;
     }
 
+    //tt ConstZero
+    /// A count type that has a zero for const construction; this is
+    /// implemented only for the count types of the crate, as a user
+    /// count type need not have one
+    ///
+    /// This is used only by const constructors, which exist only on
+    /// architectures with an assembler timer
+    #[allow(dead_code)]
+    pub(crate) trait ConstZero: Copy {
+        const ZERO: Self;
+    }
+
     //tt TraceValue
     pub(crate) trait TraceValue:
-        Default + Copy + From<crate::Delta> + Into<crate::Delta>
+        Default + Copy + PartialOrd + From<crate::Delta> + Into<crate::Delta>
     {
+        /// The largest value, to which additions saturate
+        const MAX: Self;
+        /// The zero value, for const construction
+        #[allow(dead_code)]
+        const ZERO: Self;
+        /// The zero compensation term, for const construction
+        #[allow(dead_code)]
+        const COMP_ZERO: Self::Comp;
         fn sat_add(self, other: u64) -> Self;
+        /// Saturating add, also returning false if the result saturated
+        fn sat_add_checked(self, other: u64) -> (Self, bool);
+        /// The value as an f64
+        fn as_f64(self) -> f64;
+        /// Saturating add of another value
+        fn sat_add_value(self, other: Self) -> Self;
+        /// Subtraction of another value, saturating at zero
+        fn sat_sub_value(self, other: Self) -> Self;
+        /// The value multiplied by a factor; for integers this is
+        /// rounded to the nearest, and saturates at 0 and MAX
+        fn scale(self, factor: f64) -> Self;
+        /// The compensation term of a compensated (Kahan) summation;
+        /// this is () for integers, whose additions are exact
+        type Comp: Copy + Default + core::fmt::Debug;
+        /// Saturating add, compensated using (and updating) *comp*
+        /// for types whose additions are not exact
+        #[inline(always)]
+        fn sat_add_comp(self, other: u64, comp: &mut Self::Comp) -> Self {
+            let _ = comp;
+            self.sat_add(other)
+        }
+        /// Saturating add, compensated as for `sat_add_comp`, also
+        /// returning false if the result saturated
+        #[inline(always)]
+        fn sat_add_comp_checked(self, other: u64, comp: &mut Self::Comp) -> (Self, bool) {
+            let _ = comp;
+            self.sat_add_checked(other)
+        }
+        /// Add another value with its compensation term *other_comp*,
+        /// compensated using (and updating) *comp*
+        #[inline(always)]
+        fn sat_add_value_comp(
+            self,
+            other: Self,
+            other_comp: Self::Comp,
+            comp: &mut Self::Comp,
+        ) -> Self {
+            let _ = (other_comp, comp);
+            self.sat_add_value(other)
+        }
     }
 }
 
@@ -45,26 +115,89 @@ pub(crate) mod private {
 /// u16, u32, u64, u128, usize, f32 and f64
 pub trait TraceCount: Default + Copy {
     fn sat_inc(&mut self);
+    /// Increment the count, saturating; return false if the count
+    /// was already at its maximum (and so the increment was dropped)
+    ///
+    /// By default this compares the count as a usize before and after
+    /// the increment
+    fn sat_inc_checked(&mut self) -> bool {
+        let before = self.as_usize();
+        self.sat_inc();
+        self.as_usize() != before
+    }
+    /// Add another count, saturating
+    ///
+    /// By default this increments the count *other* times, and so
+    /// should be overridden where counts may be large
+    fn sat_add_count(self, other: Self) -> Self {
+        let mut sum = self;
+        for _ in 0..other.as_usize() {
+            if !sum.sat_inc_checked() {
+                break;
+            }
+        }
+        sum
+    }
+    /// Subtract another count, saturating at zero
+    ///
+    /// By default this increments a zero count by the difference, and
+    /// so should be overridden where counts may be large
+    fn sat_sub_count(self, other: Self) -> Self {
+        let mut diff = Self::default();
+        for _ in 0..self.as_usize().saturating_sub(other.as_usize()) {
+            diff.sat_inc();
+        }
+        diff
+    }
     fn as_usize(self) -> usize;
+    /// The count as an f64
+    fn as_f64(self) -> f64 {
+        self.as_usize() as f64
+    }
+}
+
+//ip ConstZero for ()
+impl private::ConstZero for () {
+    const ZERO: Self = ();
 }
 
 //ip TraceCount for ()
 impl TraceCount for () {
     fn sat_inc(&mut self) {}
+    fn sat_inc_checked(&mut self) -> bool {
+        true
+    }
+    fn sat_add_count(self, _other: Self) -> Self {}
+    fn sat_sub_count(self, _other: Self) -> Self {}
     fn as_usize(self) -> usize {
         0
     }
 }
 
-//ip TraceCount for u8/u16/u32/u64/u128/usize
+//ip ConstZero and TraceCount for u8/u16/u32/u64/u128/usize
 macro_rules! trace_count {
     {$t:ty} => {
+        impl private::ConstZero for $t {
+            const ZERO: Self = 0;
+        }
         impl TraceCount for $t {
             #[inline(always)]
             fn sat_inc(&mut self) {
                 if *self != Self::MAX {*self = self.wrapping_add(1);}
             }
             #[inline(always)]
+            fn sat_inc_checked(&mut self) -> bool {
+                if *self != Self::MAX {*self = self.wrapping_add(1); true} else {false}
+            }
+            #[inline(always)]
+            fn sat_add_count(self, other: Self) -> Self {
+                self.saturating_add(other)
+            }
+            #[inline(always)]
+            fn sat_sub_count(self, other: Self) -> Self {
+                self.saturating_sub(other)
+            }
+            #[inline(always)]
             fn as_usize(self) -> usize {
                 self as usize
             }
@@ -78,18 +211,38 @@ trace_count!(u64);
 trace_count!(u128);
 trace_count!(usize);
 
-//ip TraceCount for f32, f64
+//ip ConstZero and TraceCount for f32, f64
 macro_rules! trace_float_count {
     {$t:ty} => {
+        impl private::ConstZero for $t {
+            const ZERO: Self = 0.0;
+        }
         impl TraceCount for $t {
             #[inline(always)]
             fn sat_inc(&mut self) {
                 *self += 1.0;
             }
             #[inline(always)]
+            fn sat_inc_checked(&mut self) -> bool {
+                *self += 1.0;
+                true
+            }
+            #[inline(always)]
+            fn sat_add_count(self, other: Self) -> Self {
+                self + other
+            }
+            #[inline(always)]
+            fn sat_sub_count(self, other: Self) -> Self {
+                (self - other).max(0.0)
+            }
+            #[inline(always)]
             fn as_usize(self) -> usize {
                 self as usize
             }
+            #[inline(always)]
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
         }
     }
 }
@@ -109,16 +262,54 @@ impl<T> TraceValue for T where T: private::TraceValue {}
 
 //ip private::TraceValue for ()
 impl private::TraceValue for () {
+    const MAX: Self = ();
+    const ZERO: Self = ();
+    const COMP_ZERO: Self::Comp = ();
     fn sat_add(self, _other: u64) -> Self {}
+    fn sat_add_checked(self, _other: u64) -> (Self, bool) {
+        ((), true)
+    }
+    fn as_f64(self) -> f64 {
+        0.0
+    }
+    fn sat_add_value(self, _other: Self) -> Self {}
+    fn sat_sub_value(self, _other: Self) -> Self {}
+    fn scale(self, _factor: f64) -> Self {}
+    type Comp = ();
 }
 
 //ip TraceValue for u8/u16/u32/u64/u128/usize
 macro_rules! trace_value {
     {$t:ty} => {
         impl private::TraceValue for $t {
+            const MAX: Self = <$t>::MAX;
+            const ZERO: Self = 0;
+            const COMP_ZERO: Self::Comp = ();
             fn sat_add(self, other:u64) -> Self {
-                self.saturating_add(other as $t)
+                self.saturating_add(<$t>::try_from(other).unwrap_or(<$t>::MAX))
+            }
+            fn sat_add_checked(self, other:u64) -> (Self, bool) {
+                match <$t>::try_from(other).ok().and_then(|o| self.checked_add(o)) {
+                    Some(v) => (v, true),
+                    None => (<$t>::MAX, false),
+                }
+            }
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+            fn sat_add_value(self, other: Self) -> Self {
+                self.saturating_add(other)
             }
+            fn sat_sub_value(self, other: Self) -> Self {
+                self.saturating_sub(other)
+            }
+            fn scale(self, factor: f64) -> Self {
+                // Float to integer 'as' casts truncate and saturate
+                // (and NaN is 0); adding 0.5 then rounds to the
+                // nearest (f64::round is not in core)
+                ((self as f64) * factor + 0.5) as $t
+            }
+            type Comp = ();
         }
     }
 }
@@ -133,21 +324,99 @@ trace_value!(usize);
 macro_rules! trace_float_value {
     {$t:ty} => {
         impl private::TraceValue for $t {
+            const MAX: Self = <$t>::MAX;
+            const ZERO: Self = 0.0;
+            const COMP_ZERO: Self::Comp = 0.0;
             fn sat_add(self, other:u64) -> Self {
                 self + (other as $t)
             }
+            fn sat_add_checked(self, other:u64) -> (Self, bool) {
+                (self + (other as $t), true)
+            }
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+            fn sat_add_value(self, other: Self) -> Self {
+                self + other
+            }
+            fn sat_sub_value(self, other: Self) -> Self {
+                (self - other).max(0.0)
+            }
+            fn scale(self, factor: f64) -> Self {
+                ((self as f64) * factor) as $t
+            }
+            type Comp = Self;
+            #[inline(always)]
+            fn sat_add_comp(self, other: u64, comp: &mut Self) -> Self {
+                // Kahan summation: *comp* holds the low-order part lost
+                // from the previous addition, which is fed back in
+                let y = (other as $t) - *comp;
+                let t = self + y;
+                *comp = (t - self) - y;
+                t
+            }
+            #[inline(always)]
+            fn sat_add_comp_checked(self, other: u64, comp: &mut Self) -> (Self, bool) {
+                // A float addition does not saturate
+                (self.sat_add_comp(other, comp), true)
+            }
+            #[inline(always)]
+            fn sat_add_value_comp(self, other: Self, other_comp: Self, comp: &mut Self) -> Self {
+                // The sum of the low-order parts lost by both is fed
+                // back in with the addition
+                let y = other - (*comp + other_comp);
+                let t = self + y;
+                *comp = (t - self) - y;
+                t
+            }
         }
     }
 }
 trace_float_value!(f32);
 trace_float_value!(f64);
 
+//tt Startable
+/// A timer whose start can be set from a tick value captured
+/// elsewhere (such as by [crate::anchor])
+///
+/// This is implemented by the accumulator and trace types, so that
+/// [crate::start_all] can give several of them exactly the same start;
+/// *A* is the architecture of the timer, whose ticks are given
+pub trait Startable<const S: bool, A: TArch = crate::TDesc<S>> {
+    /// Start the timer as if `start` had been invoked when the timer
+    /// read *ticks*
+    fn start_at(&mut self, ticks: u64);
+}
+
+//tt RecordDelta
+/// A store of time deltas, such as an accumulator or a histogram,
+/// that a [crate::Measured] guard can record into
+///
+/// This is implemented by [crate::AccTimer], [crate::HistTimer],
+/// [crate::AdaptiveTimer], [crate::SampleRing] and (for one index)
+/// [crate::AccSlot], so that the store can be changed without changing
+/// the code that measures.
+pub trait RecordDelta<const S: bool> {
+    /// Record a delta (in the ticks of the timer)
+    fn record(&mut self, delta: u64);
+}
+
+//ip RecordDelta for &mut R
+impl<const S: bool, R: RecordDelta<S> + ?Sized> RecordDelta<S> for &mut R {
+    fn record(&mut self, delta: u64) {
+        (**self).record(delta);
+    }
+}
+
 //tt TArch
 /// Trait provided for architecture-specific timers
 ///
 /// This is supported by a single assembler timer and a standard
 /// (std::time) timer; it is shown by looking for its implementation on the type [crate::TDesc]
 ///
+/// It is also implemented by [crate::CustomArch], for a user-provided
+/// [CustomCounter]
+///
 /// This is used in a 'where' clause for a type, e.g.
 ///
 /// ```ignore
@@ -157,7 +426,28 @@ trace_float_value!(f64);
 /// ```
 ///
 #[allow(private_bounds)]
-pub trait TArch: private::ArchDesc {}
+pub trait TArch: private::ArchDesc {
+    /// The unit of the timer values, [crate::Ticks] for an assembler
+    /// timer and [crate::Nanos] for a std::time timer
+    type Unit: TimerUnit;
+}
 
-//ip TArch for T: private::ArchDesc
-impl<T> TArch for T where T: private::ArchDesc {}
+//tt CustomCounter
+/// Trait for a counter that the crate does not know about (such as a
+/// memory-mapped FPGA or driver timer), to be used as the architecture
+/// of the timers with [crate::CustomArch]
+///
+/// The counter should be monotonic (other than wrapping), and cheap
+/// to read, as it is read on every timer start and stop
+pub trait CustomCounter {
+    /// Read the counter
+    fn now() -> u64;
+}
+
+//tt TimerUnit
+/// A unit for timer values, wrapping a u64; this is implemented by
+/// [crate::Ticks] and [crate::Nanos]
+pub trait TimerUnit:
+    core::fmt::Debug + core::fmt::Display + Copy + PartialEq + PartialOrd + From<u64> + Into<u64>
+{
+}
@@ -0,0 +1,63 @@
+//a Imports
+use crate::TimerUnit;
+
+//a Ticks
+//tp Ticks
+/// A timer value in CPU counter ticks, as returned by an assembler
+/// timer
+///
+/// This displays with a 'ticks' suffix
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ticks(pub u64);
+
+//a Nanos
+//tp Nanos
+/// A timer value in nanoseconds, as returned by a std::time timer
+///
+/// This displays with an 'ns' suffix
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nanos(pub u64);
+
+//ip Nanos
+impl Nanos {
+    //ap as_duration
+    /// Return the value as a Duration
+    pub fn as_duration(self) -> core::time::Duration {
+        core::time::Duration::from_nanos(self.0)
+    }
+}
+
+//ip Display for Ticks
+impl core::fmt::Display for Ticks {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(fmt, "{} ticks", self.0)
+    }
+}
+
+//ip Display for Nanos
+impl core::fmt::Display for Nanos {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write!(fmt, "{} ns", self.0)
+    }
+}
+
+//ip From/Into u64 and TimerUnit for Ticks/Nanos
+macro_rules! timer_unit {
+    {$t:ty} => {
+        impl From<u64> for $t {
+            #[inline(always)]
+            fn from(v: u64) -> Self {
+                Self(v)
+            }
+        }
+        impl From<$t> for u64 {
+            #[inline(always)]
+            fn from(v: $t) -> Self {
+                v.0
+            }
+        }
+        impl TimerUnit for $t {}
+    }
+}
+timer_unit!(Ticks);
+timer_unit!(Nanos);
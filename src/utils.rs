@@ -0,0 +1,89 @@
+//a Useful functions
+//fp percentage
+/// Return *part* as a percentage of *total*, clamped to 0 to 100
+///
+/// This is computed in floating point, so it cannot overflow for
+/// large (e.g. billions of ticks) values; a total of zero (or less)
+/// yields 0%.
+pub(crate) fn percentage(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (100.0 * part / total).clamp(0.0, 100.0)
+    } else {
+        0.0
+    }
+}
+
+//fp emit_metric
+/// Export an accumulated value and count for an entry to the
+/// `metrics` crate, as absolute counters named `<prefix>.<name>.ticks`
+/// and `<prefix>.<name>.count`
+#[cfg(feature = "metrics")]
+pub(crate) fn emit_metric(prefix: &str, name: &str, acc: f64, cnt: usize) {
+    metrics::counter!(format!("{prefix}.{name}.ticks")).absolute(acc as u64);
+    metrics::counter!(format!("{prefix}.{name}.count")).absolute(cnt as u64);
+}
+
+//fp write_csv
+/// Write accumulated values and counts as CSV, with a header of
+/// `index,sum,count,avg` and then one row per entry
+///
+/// If the count type is () (which does not count) then the count and
+/// average columns are blank; the average is also blank for a count of
+/// zero
+#[cfg(feature = "csv")]
+pub(crate) fn write_csv<W, T, C, I>(w: &mut W, acc_cnts: I) -> std::io::Result<()>
+where
+    W: std::io::Write,
+    T: crate::TraceValue + core::fmt::Display,
+    C: crate::TraceCount,
+    I: Iterator<Item = (T, C)>,
+{
+    writeln!(w, "index,sum,count,avg")?;
+    let counted = core::mem::size_of::<C>() != 0;
+    for (i, (acc, cnt)) in acc_cnts.enumerate() {
+        if !counted {
+            writeln!(w, "{i},{acc},,")?;
+        } else if cnt.as_usize() == 0 {
+            writeln!(w, "{i},{acc},0,")?;
+        } else {
+            let avg = acc.as_f64() / cnt.as_f64();
+            writeln!(w, "{i},{acc},{},{avg}", cnt.as_usize())?;
+        }
+    }
+    Ok(())
+}
+
+//fp to_json
+/// Render accumulated values and counts as a JSON summary, of the form
+/// `{"slots":[{"index":0,"sum":123,"count":4,"avg":30.75},...]}`
+///
+/// If the count type is () (which does not count) then the count and
+/// average are null; the average is also null for a count of zero
+#[cfg(feature = "json")]
+pub(crate) fn to_json<T, C, I>(acc_cnts: I) -> String
+where
+    T: crate::TraceValue + core::fmt::Display,
+    C: crate::TraceCount,
+    I: Iterator<Item = (T, C)>,
+{
+    use core::fmt::Write;
+    let counted = core::mem::size_of::<C>() != 0;
+    let mut json = String::from("{\"slots\":[");
+    for (i, (acc, cnt)) in acc_cnts.enumerate() {
+        if i != 0 {
+            json.push(',');
+        }
+        // Writing to a String cannot fail
+        let _ = write!(json, "{{\"index\":{i},\"sum\":{acc},");
+        if !counted {
+            json.push_str("\"count\":null,\"avg\":null}");
+        } else if cnt.as_usize() == 0 {
+            json.push_str("\"count\":0,\"avg\":null}");
+        } else {
+            let avg = acc.as_f64() / cnt.as_f64();
+            let _ = write!(json, "\"count\":{},\"avg\":{avg}}}", cnt.as_usize());
+        }
+    }
+    json.push_str("]}");
+    json
+}
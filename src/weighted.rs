@@ -0,0 +1,87 @@
+//a Imports
+use crate::{BaseTimer, TArch, TDesc};
+
+//a TimeWeightedAvg
+//tp TimeWeightedAvg
+/// A [TimeWeightedAvg] records the time-weighted average of a
+/// fluctuating quantity (a gauge, such as a queue depth), where each
+/// value persists until the next is set
+///
+/// Each 'set' weights the *previous* value by the ticks that have
+/// elapsed since it was set; the average is then the sum of the
+/// weighted values divided by the total ticks. The first 'set' (after
+/// creation or a clear) just records the value and starts the timer.
+///
+/// ```
+/// # use cpu_timer::TimeWeightedAvg;
+/// let mut q = TimeWeightedAvg::<true>::default();
+/// q.set(3.0);
+/// // ... the queue has depth 3 for a while
+/// q.set(5.0);
+/// // ... and then depth 5
+/// q.set(0.0);
+/// println!("The average queue depth was {}", q.average());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimeWeightedAvg<const S: bool>
+where
+    TDesc<S>: TArch,
+{
+    base: BaseTimer<S>,
+    started: bool,
+    value: f64,
+    weighted: f64,
+    ticks: u64,
+}
+
+//ip TimeWeightedAvg
+impl<const S: bool> TimeWeightedAvg<S>
+where
+    TDesc<S>: TArch,
+{
+    //mp clear
+    /// Clear the accumulated values; the next 'set' restarts the timer
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    //mp set
+    /// Set the current value of the quantity, accumulating the
+    /// previous value weighted by the ticks since it was set
+    pub fn set(&mut self, value: f64) {
+        if self.started {
+            let dt = self.base.elapsed_and_update();
+            self.weighted += self.value * dt as f64;
+            self.ticks = self.ticks.saturating_add(dt);
+        } else {
+            self.base.start();
+            self.started = true;
+        }
+        self.value = value;
+    }
+
+    //ap value
+    /// Return the current value of the quantity
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    //ap ticks
+    /// Return the total ticks over which values have been accumulated
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    //ap average
+    /// Return the time-weighted average of the values, up to the last
+    /// 'set'
+    ///
+    /// If no time has been accumulated then this is the current value
+    pub fn average(&self) -> f64 {
+        if self.ticks == 0 {
+            self.value
+        } else {
+            self.weighted / self.ticks as f64
+        }
+    }
+}
@@ -1,7 +1,18 @@
 //a Imports
-use cpu_timer::{AccArray, AccVec};
+use cpu_timer::{
+    anchor, rate, tick_rate, AccArray, AccArray2D, AccStat, AccTrace, AccVec, CustomArch,
+    CustomCounter, Startable, TraceCount,
+};
+
+/// Busy-wait for a number of milliseconds; this is used rather than
+/// sleeping so that the time is also seen with the `cpu-time` feature
+fn spin_ms(ms: u64) {
+    let now = std::time::Instant::now();
+    while now.elapsed() < std::time::Duration::from_millis(ms) {}
+}
 
 #[test]
+#[allow(unused_variables)]
 fn stuff() {
     let mut _ac = AccVec::<true, u32, u32>::with_capacity(4);
     let mut ac = AccArray::<true, f64, f64, 8>::default();
@@ -15,3 +26,925 @@ fn stuff() {
     println!("{ac}");
     //    assert!(false);
 }
+
+#[test]
+fn acc_n_checked() {
+    let mut ac = AccArray::<true, u64, u8, 2>::default();
+    ac.start();
+    for _ in 0..255 {
+        assert!(ac.acc_n_checked(0), "Count should not yet have saturated");
+    }
+    assert!(!ac.acc_n_checked(0), "Count should have saturated");
+    assert_eq!(ac.cnts()[0], 255);
+    assert!(ac.acc_n_checked(1), "Other counts are unaffected");
+    assert!(!ac.acc_n_checked(2), "Out of range accumulates nothing");
+}
+
+/// A count type outside the crate, which saturates at 3, and relies
+/// on the provided methods of TraceCount
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Count3(u8);
+impl TraceCount for Count3 {
+    fn sat_inc(&mut self) {
+        self.0 = (self.0 + 1).min(3);
+    }
+    fn as_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[test]
+fn acc_n_checked_custom_count() {
+    let mut ac = AccArray::<true, u64, Count3, 1>::default();
+    ac.start();
+    for _ in 0..3 {
+        assert!(ac.acc_n_checked(0), "Count should not yet have saturated");
+    }
+    assert!(!ac.acc_n_checked(0), "Count should have saturated");
+    assert_eq!(ac.cnts()[0], Count3(3));
+
+    assert_eq!(Count3(2).sat_add_count(Count3(2)), Count3(3));
+    assert_eq!(Count3(1).sat_add_count(Count3(1)), Count3(2));
+    assert_eq!(Count3(3).sat_sub_count(Count3(1)), Count3(2));
+    assert_eq!(Count3(1).sat_sub_count(Count3(2)), Count3(0));
+}
+
+#[test]
+fn with_values() {
+    let mut ac = AccArray::<true, u64, u32, 2>::with_values([100, 200], [1, 2]);
+    ac.start();
+    ac.acc_n(1);
+    assert_eq!(ac.accs()[0], 100);
+    assert!(
+        ac.accs()[1] >= 200,
+        "Accumulation continues from the preloaded value"
+    );
+    assert_eq!(ac.cnts(), &[1, 3]);
+}
+
+#[test]
+fn scope() {
+    let mut ac = AccArray::<true, u64, u32, 4>::default();
+    let f = |ac: &mut AccArray<true, u64, u32, 4>, early: bool| -> Option<()> {
+        let _g = ac.scope(2);
+        if early {
+            return None;
+        }
+        Some(())
+    };
+    assert!(f(&mut ac, true).is_none());
+    assert!(f(&mut ac, false).is_some());
+    assert_eq!(ac.cnts(), &[0, 0, 2, 0], "Both exits accumulated");
+}
+
+#[test]
+fn scope_nested() {
+    let mut ac = AccArray::<true, u64, u32, 4>::default();
+    let now = cpu_timer::raw::now::<true>();
+    ac.start_at(now.wrapping_sub(1_000_000));
+    {
+        let mut g = ac.scope(1);
+        {
+            let _g = g.scope(2);
+        }
+        assert_eq!(g.cnts(), &[0, 0, 1, 0], "Inner guard dropped first");
+    }
+    ac.acc_n(0);
+    assert_eq!(ac.cnts(), &[1, 1, 1, 0]);
+    assert!(
+        ac.accs()[0] >= 1_000_000,
+        "The outer start is not restarted"
+    );
+    assert!(ac.accs()[1] < 1_000_000, "A guard measures just its region");
+    assert!(ac.accs()[2] <= ac.accs()[1]);
+}
+
+#[test]
+fn percentages() {
+    let ac = AccArray::<true, u64, u32, 3>::with_values([u64::MAX / 2, u64::MAX / 2, 0], [1, 1, 0]);
+    let p = ac.percentages();
+    assert!((p[0] - 50.0).abs() < 1e-9, "Large values do not overflow");
+    assert!((p[1] - 50.0).abs() < 1e-9);
+    assert_eq!(p[2], 0.0);
+
+    let ac = AccArray::<true, u64, u32, 2>::default();
+    assert_eq!(ac.percentages(), [0.0, 0.0], "Zero total gives 0%");
+
+    let av = AccVec::<true, u64, u32>::with_capacity(2);
+    assert_eq!(av.percentages(), vec![0.0, 0.0], "Zero total gives 0%");
+}
+
+#[test]
+fn acc_n_if_over() {
+    let mut ac = AccArray::<false, u64, u32, 2>::default();
+    ac.start();
+    ac.acc_n(0);
+    ac.acc_n_if_over(1, u64::MAX);
+    assert_eq!(ac.cnts(), &[1, 0], "Nothing is over u64::MAX");
+    spin_ms(2);
+    ac.acc_n_if_over(1, 1_000_000);
+    assert_eq!(ac.cnts(), &[1, 1], "2ms busy-wait is over 1ms");
+    assert!(ac.accs()[1] > 1_000_000);
+}
+
+#[cfg(feature = "sequence")]
+#[test]
+fn last_sequence() {
+    let mut av = AccVec::<true, u64, u32>::with_capacity(3);
+    av.start();
+    av.acc_n(2);
+    av.acc_push();
+    av.acc_n_restart(1);
+    av.acc_n(7);
+    av.acc_push_restart();
+    av.acc_push();
+    av.acc_push();
+    assert_eq!(
+        av.last_sequence(),
+        &[2, 0, 1, 1, 2, 3],
+        "Out of range index not recorded"
+    );
+    av.start();
+    assert!(av.last_sequence().is_empty());
+}
+
+#[test]
+fn outlier_threshold() {
+    let mut ac = AccArray::<false, u64, u32, 2>::default();
+    ac.set_outlier_threshold(1_000_000);
+    ac.start();
+    ac.acc_n(0);
+    spin_ms(2);
+    ac.acc_n(1);
+    assert_eq!(ac.cnts(), &[1, 0], "2ms busy-wait is an outlier");
+    assert_eq!(ac.accs()[1], 0);
+    assert_eq!(ac.rejected_count(), 1);
+    ac.clear();
+    assert_eq!(ac.rejected_count(), 0);
+    ac.start();
+    spin_ms(2);
+    ac.acc_n(1);
+    assert_eq!(ac.rejected_count(), 1, "Threshold kept across clear");
+}
+
+#[test]
+fn acc_vec_index_invariant() {
+    // A simple xorshift generator, so that the sequences are
+    // reproducible without any extra dependencies
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut rnd = move |n: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % n
+    };
+    for _ in 0..200 {
+        let mut av = AccVec::<true, u64, u32>::with_capacity(rnd(4) as usize);
+        let mut pushed = 0;
+        for _ in 0..50 {
+            match rnd(6) {
+                0 => {
+                    av.start();
+                    pushed = 0;
+                }
+                1 => {
+                    av.clear();
+                    pushed = 0;
+                }
+                2 => av.acc_n(rnd(6) as usize),
+                3 => av.acc_n_restart(rnd(6) as usize),
+                4 => {
+                    assert_eq!(av.acc_push(), pushed);
+                    pushed += 1;
+                }
+                _ => {
+                    assert_eq!(av.acc_push_restart(), pushed);
+                    pushed += 1;
+                }
+            }
+            assert_eq!(av.acc_cnts().len(), pushed);
+            assert!(av.acc_cnts().len() <= av.all_acc_cnts().len());
+        }
+    }
+}
+
+#[test]
+fn geomean() {
+    let mut av = AccVec::<true, u64, u32>::default();
+    assert_eq!(av.geomean(), 0.0, "No counts gives 0");
+    av.record(100);
+    av.record(400);
+    assert!((av.geomean() - 200.0).abs() < 1e-9);
+
+    let mut sparse = AccVec::<true, u64, u32>::with_capacity(3);
+    sparse.merge(&av);
+    assert_eq!(sparse.all_acc_cnts()[2], (0, 0));
+    assert!(
+        (sparse.geomean() - 200.0).abs() < 1e-9,
+        "Zero count entry is skipped"
+    );
+}
+
+#[test]
+fn take() {
+    let mut ac = AccArray::<true, u64, u32, 2>::with_values([100, 200], [1, 2]);
+    ac.start();
+    let taken = ac.take();
+    assert_eq!(taken.accs(), &[100, 200]);
+    assert_eq!(taken.cnts(), &[1, 2]);
+    assert_eq!(ac.accs(), &[0, 0], "Reset after take");
+    assert_eq!(ac.cnts(), &[0, 0], "Reset after take");
+    ac.acc_n(1);
+    assert_eq!(ac.cnts(), &[0, 1], "Timer still running after take");
+}
+
+#[test]
+fn display_precision() {
+    let mut ac = AccArray::<true, u64, u64, 2>::with_values([10, 0], [4, 0]);
+    assert_eq!(format!("{ac}"), "[(10, 4, 2), (0, 0, -)]");
+    ac.set_display_precision(2);
+    assert_eq!(format!("{ac}"), "[(10, 4, 2.50), (0, 0, -)]");
+}
+
+#[test]
+fn clear_incremental() {
+    let mut ac = AccArray::<true, u64, u32, 256>::default();
+    ac.start();
+    ac.acc_n(3);
+    ac.acc_n(7);
+    ac.clear_incremental();
+    assert!(ac.cnts().iter().all(|c| *c == 0));
+    assert!(ac.accs().iter().all(|a| *a == 0));
+
+    let mut ac = AccArray::<true, u64, u32, 4>::with_values([1, 2, 3, 4], [1, 1, 1, 1]);
+    ac.clear_incremental();
+    assert_eq!(ac.cnts(), &[0, 0, 0, 0], "Preloaded values are all cleared");
+}
+
+#[test]
+fn merge_all() {
+    let mut a = AccVec::<true, u8, u8>::with_capacity(1);
+    let mut b = AccVec::<true, u8, u8>::with_capacity(3);
+    for _ in 0..200 {
+        a.start();
+        a.acc_n(0);
+        b.start();
+        b.acc_n(0);
+        b.acc_n(2);
+    }
+    let m = AccVec::merge_all(&[a.clone(), b.clone()]);
+    let ac = m.all_acc_cnts();
+    assert_eq!(ac.len(), 3, "Sized to the longest");
+    assert_eq!(ac[0].1, 255, "Counts saturate");
+    assert_eq!(ac[1].1, 0);
+    assert_eq!(ac[2].1, 200);
+    assert_eq!(
+        ac[0].0,
+        a.all_acc_cnts()[0].0.saturating_add(b.all_acc_cnts()[0].0)
+    );
+    assert!(AccVec::<true, u8, u8>::merge_all(&[])
+        .all_acc_cnts()
+        .is_empty());
+}
+
+#[test]
+fn acc_n_span() {
+    let mut ac = AccArray::<true, u64, u32, 2>::default();
+    let t0 = anchor::<true>();
+    let t1 = anchor::<true>();
+    ac.acc_n_span(1, 100, 150);
+    ac.acc_n_span(1, t0, t1);
+    ac.acc_n_span(2, 0, 1);
+    assert_eq!(ac.cnts(), &[0, 2], "Out of range index ignored");
+    assert_eq!(ac.accs()[1], 50 + t1.wrapping_sub(t0));
+}
+
+#[test]
+fn rates() {
+    let old = AccArray::<true, u64, u32, 3>::with_values([100, 0, 50], [10, 0, 5]);
+    let new = AccArray::<true, u64, u32, 3>::with_values([300, 40, 0], [30, 4, 0]);
+    assert_eq!(rate(&new, &old, 2.0), [10.0, 2.0, 0.0], "Decrease clamped");
+    assert_eq!(tick_rate(&new, &old, 2.0), [100.0, 20.0, 0.0]);
+    assert_eq!(rate(&new, &old, 0.0), [0.0; 3], "No elapsed time");
+}
+
+#[test]
+fn time_result() {
+    let mut ac = AccArray::<true, u64, u32, 2>::default();
+    let parse = |s: &str| s.parse::<u32>();
+    assert_eq!(ac.time_result(0, 1, || parse("12")), Ok(12));
+    assert!(ac.time_result(0, 1, || parse("x")).is_err());
+    assert!(ac.time_result(0, 1, || parse("y")).is_err());
+    assert_eq!(ac.cnts(), &[1, 2]);
+}
+
+/// A fake counter, that advances by 10 ticks on every read
+struct Ticker;
+static TICKER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+impl CustomCounter for Ticker {
+    fn now() -> u64 {
+        TICKER.fetch_add(10, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn acc_by() {
+    let mut ac = AccArray::<true, u64, u32, 3, CustomArch<Ticker>>::default();
+    for op in [1, 2, 1] {
+        let r = ac.acc_by(|| (op, op * 10));
+        assert_eq!(r, op * 10);
+    }
+    assert_eq!(ac.cnts(), &[0, 2, 1]);
+    assert_eq!(ac.accs(), &[0, 20, 10], "Time of each f is accumulated");
+}
+
+#[test]
+fn sum_where() {
+    let mut t = AccVec::<true, u64, u32>::with_capacity(4);
+    t.set_label(0, "db.read");
+    t.set_label(1, "db.write");
+    t.set_label(3, "render");
+    assert_eq!(t.label(2), None);
+    assert_eq!(t.label(3), Some("render"));
+    for _ in 0..10 {
+        t.start();
+        for i in 0..4 {
+            t.acc_n(i);
+        }
+    }
+    let ac = t.all_acc_cnts();
+    let (db, db_count) = t.sum_where(|l| l.starts_with("db."));
+    assert_eq!(db_count, 20);
+    assert_eq!(db, ac[0].0 + ac[1].0);
+    assert_eq!(
+        t.sum_where(|_| true).1,
+        30,
+        "Unlabelled entries are excluded"
+    );
+    assert_eq!(t.sum_where(|l| l == "none"), (0, 0));
+}
+
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+))]
+#[test]
+fn const_new() {
+    use std::sync::Mutex;
+    static COUNTERS: Mutex<AccArray<true, u64, u32, 4>> = Mutex::new(AccArray::new());
+    let mut c = COUNTERS.lock().unwrap();
+    assert_eq!(c.cnts(), &[0; 4]);
+    c.start();
+    c.acc_n(1);
+    assert_eq!(c.cnts(), &[0, 1, 0, 0]);
+    assert!(c.accs()[1] < 1 << 40, "Started before accumulating");
+
+    static FLOATS: AccArray<true, f64, f32, 2> = AccArray::new();
+    assert_eq!(FLOATS.accs(), &[0.0; 2]);
+    assert_eq!(FLOATS.cnts(), &[0.0; 2]);
+    assert!(FLOATS.is_empty());
+}
+
+#[test]
+fn min_max() {
+    let mut ac = AccArray::<true, u64, u64, 3>::default();
+    assert_eq!(ac.mins(), &[u64::MAX; 3]);
+    assert_eq!(ac.maxs(), &[0; 3]);
+    for (s, e) in [(100, 130), (200, 210), (300, 350)] {
+        ac.acc_n_span(1, s, e);
+    }
+    assert_eq!(ac.mins()[1], 10);
+    assert_eq!(ac.maxs()[1], 50);
+    assert_eq!(ac.mins()[0], u64::MAX, "Unused index is untouched");
+    ac.set_display_range(true);
+    assert_eq!(
+        format!("{ac}"),
+        "[(0, 0, -), (90, 3, 30, 10..50), (0, 0, -)]"
+    );
+    ac.clear_incremental();
+    assert_eq!(ac.mins(), &[u64::MAX; 3]);
+    assert_eq!(ac.maxs(), &[0; 3]);
+    ac.acc_n_span(2, 0, 7);
+    ac.clear();
+    assert_eq!(ac.maxs(), &[0; 3]);
+}
+
+#[test]
+fn acc_vec_percentiles() {
+    let mut av = AccVec::<true, u64, u32>::with_samples(1);
+    for _ in 0..20 {
+        av.start();
+        av.acc_push_restart();
+        av.acc_push();
+    }
+    let ac = av.all_acc_cnts();
+    assert_eq!(ac.len(), 2, "Pushed beyond the initial size");
+    let p = av.percentiles(1, &[0.0, 50.0, 100.0]);
+    assert!(p[0] <= p[1] && p[1] <= p[2]);
+    assert!(p[2] <= ac[1].0, "No single sample exceeds the total");
+    assert_eq!(av.percentiles(5, &[50.0]), vec![0]);
+    av.clear();
+    assert_eq!(av.percentiles(1, &[50.0]), vec![0]);
+
+    let av = AccVec::<true, u64, u32>::with_capacity(1);
+    assert_eq!(
+        av.percentiles(0, &[50.0, 99.0]),
+        vec![0, 0],
+        "No samples kept"
+    );
+}
+
+#[test]
+fn acc_array_2d() {
+    let mut t = AccArray2D::<true, u64, u32, 2, 3>::default();
+    t.start();
+    t.acc_rc(0, 2);
+    t.acc_rc(1, 0);
+    t.acc_rc(1, 0);
+    t.acc_rc(2, 0);
+    t.acc_rc(0, 3);
+    t.acc_rc_restart(1, 1);
+    assert_eq!(t.cnts(), &[[0, 0, 1], [2, 1, 0]], "Out of range is ignored");
+    let t = AccArray2D::<false, u64, u64, 2, 2>::default();
+    assert_eq!(
+        format!("{t}"),
+        "[(0, 0, -), (0, 0, -)]\n[(0, 0, -), (0, 0, -)]"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    let ac = AccArray::<true, u64, u32, 2>::with_values([100, 200], [1, 2]);
+    let json = serde_json::to_string(&ac).unwrap();
+    assert_eq!(json, r#"{"accs":[100,200],"cnts":[1,2]}"#);
+    let mut de: AccArray<true, u64, u32, 2> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de.accs(), &[100, 200]);
+    assert_eq!(de.cnts(), &[1, 2]);
+    de.start();
+    de.acc_n(0);
+    assert_eq!(de.cnts(), &[2, 2], "Deserialized timer is usable");
+    assert!(serde_json::from_str::<AccArray<true, u64, u32, 3>>(&json).is_err());
+
+    let mut av = AccVec::<true, u64, u32>::with_capacity(2);
+    av.start();
+    av.acc_n(1);
+    let json = serde_json::to_string(&av).unwrap();
+    let de: AccVec<true, u64, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(de.all_acc_cnts(), av.all_acc_cnts());
+}
+
+#[test]
+fn export() {
+    let mut ac = AccArray::<true, u64, u32, 3>::with_values([10, 20, 0], [1, 4, 0]);
+    let report = ac.export();
+    assert_eq!(report.entries[0].label, "0", "Unlabelled uses the index");
+    ac.set_labels(&["parse", "check", "emit"]);
+    let report = ac.export();
+    assert_eq!(report.entries.len(), 3);
+    assert_eq!(report.entries[1].label, "check");
+    assert_eq!((report.entries[1].value, report.entries[1].count), (20, 4));
+    assert_eq!(
+        format!("{report}"),
+        "parse: (10, 1, 10)\ncheck: (20, 4, 5)\nemit: (0, 0, -)"
+    );
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&report).unwrap();
+        let de: cpu_timer::TimingReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, report);
+    }
+    ac.clear();
+    assert_eq!(ac.label(2), Some("emit"), "Labels kept on clear");
+
+    let mut av = AccVec::<true, u64, u32>::with_capacity(2);
+    av.set_label(1, "db");
+    let report = av.export();
+    assert_eq!(report.entries[0].label, "0");
+    assert_eq!(report.entries[1].label, "db");
+}
+
+#[test]
+fn merge() {
+    let workers: Vec<_> = (1..=3_u64)
+        .map(|n| AccArray::<true, u64, u32, 3>::with_values([n, 10 * n, 0], [1, n as u32, 0]))
+        .collect();
+    let mut m = workers[0];
+    m.merge(&workers[1]);
+    m.merge(&workers[2]);
+    assert_eq!(m.accs(), &[6, 60, 0]);
+    assert_eq!(m.cnts(), &[3, 6, 0]);
+    let total = AccArray::merge_from_iter(workers);
+    assert_eq!(total.accs(), m.accs());
+    assert_eq!(total.cnts(), m.cnts());
+
+    let mut a = AccVec::<true, u64, u32>::with_capacity(1);
+    let mut b = AccVec::<true, u64, u32>::with_capacity(3);
+    let mut c = AccVec::<true, u64, u32>::with_capacity(2);
+    for v in [&mut a, &mut b, &mut c] {
+        v.start();
+        v.acc_n(0);
+        v.acc_n(1);
+        v.acc_n(2);
+    }
+    let sums: Vec<u64> = (0..3)
+        .map(|i| {
+            [&a, &b, &c]
+                .iter()
+                .filter_map(|v| v.all_acc_cnts().get(i))
+                .map(|ac| ac.0)
+                .sum()
+        })
+        .collect();
+    a.merge(&b);
+    a.merge(&c);
+    let ac = a.all_acc_cnts();
+    assert_eq!(ac.len(), 3, "Extended to the longest");
+    assert_eq!(ac.iter().map(|ac| ac.1).collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert_eq!(ac.iter().map(|ac| ac.0).collect::<Vec<_>>(), sums);
+}
+
+#[test]
+fn acc_vec_record() {
+    let mut log = AccVec::<true, u64, u32>::with_capacity(2);
+    assert_eq!(log.record(5), 2, "Appended after the existing entries");
+    assert_eq!(log.record(5), 3, "Never accumulated into an entry");
+    assert_eq!(log.record(7), 4);
+    assert_eq!(&log.all_acc_cnts()[2..], &[(5, 1), (5, 1), (7, 1)]);
+    assert_eq!(log.acc_cnts().len(), 5);
+}
+
+#[test]
+fn is_empty() {
+    let mut ac = AccArray::<true, u64, u32, 2>::default();
+    assert!(ac.is_empty());
+    ac.start();
+    ac.acc_n(1);
+    assert!(!ac.is_empty());
+    ac.clear();
+    assert!(ac.is_empty());
+    assert!(
+        AccArray::<true, u64, u32, 2>::with_values([1, 0], [0, 0]).is_empty(),
+        "Only the counts are considered"
+    );
+    assert!(!AccArray::<true, u64, u32, 2>::with_values([0, 0], [0, 1]).is_empty());
+
+    let mut av = AccVec::<true, u64, u32>::with_capacity(2);
+    assert!(av.is_empty());
+    av.start();
+    av.acc_n(0);
+    assert!(!av.is_empty());
+    av.clear();
+    assert!(av.is_empty());
+    av.record(0);
+    assert!(!av.is_empty(), "A zero delta is still counted");
+}
+
+#[test]
+fn scale() {
+    let mut a = AccArray::<true, u64, u32, 3>::with_values([100, 3, u64::MAX / 2], [4, 1, 1]);
+    a.scale(2.5);
+    assert_eq!(a.accs(), &[250, 8, u64::MAX], "Rounded and saturated");
+    assert_eq!(a.cnts(), &[4, 1, 1]);
+    a.scale(-1.0);
+    assert_eq!(a.accs(), &[0, 0, 0], "Saturates at zero");
+
+    let f = AccArray::<true, f64, u32, 2>::with_values([10.0, 1.0], [1, 1]);
+    let g = f.scaled(0.25);
+    assert_eq!(f.accs(), &[10.0, 1.0]);
+    assert_eq!(g.accs(), &[2.5, 0.25]);
+
+    let mut m = AccArray::<true, u64, u32, 2>::default();
+    m.start();
+    m.acc_n(0);
+    let max = m.maxs()[0];
+    m.scale(2.0);
+    assert_eq!(m.maxs()[0], max * 2);
+    assert_eq!(m.mins()[1], u64::MAX, "Unused minimum left unchanged");
+}
+
+#[test]
+fn iter_stats() {
+    let a = AccArray::<true, u64, u64, 3>::with_values([10, 0, 90], [2, 0, 3]);
+    let stats: Vec<_> = a.iter_stats().collect();
+    assert_eq!(
+        stats[0],
+        AccStat {
+            index: 0,
+            sum: 10,
+            count: 2,
+            avg: Some(5)
+        }
+    );
+    assert_eq!(stats[1].avg, None, "No average for a zero count");
+    assert_eq!(stats[2].avg, Some(30));
+
+    let mut v = AccVec::<true, f64, f64>::with_capacity(1);
+    v.record(3);
+    v.record(7);
+    let mut stats: Vec<_> = v.iter_stats().collect();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[0].avg, None);
+    assert_eq!(stats[1].avg, Some(3.0));
+    stats.sort_by(|a, b| b.avg.partial_cmp(&a.avg).unwrap());
+    assert_eq!(stats[0].index, 2, "Hottest entry first");
+}
+
+#[test]
+fn worst_regression() {
+    let mut baseline = AccVec::<true, u64, u32>::default();
+    for (l, d) in [("a", 100), ("b", 200), ("", 50)] {
+        let n = baseline.record(d);
+        baseline.set_label(n, l);
+    }
+    let mut current = AccVec::<true, u64, u32>::default();
+    for (l, d) in [("b", 300), ("a", 100), ("", 80), ("c", 1000)] {
+        let n = current.record(d);
+        current.set_label(n, l);
+    }
+    let (i, r) = current.worst_regression(&baseline).unwrap();
+    assert_eq!(i, 2, "Unlabelled entry compared by index; 'c' ignored");
+    assert!((r - 0.6).abs() < 1e-9);
+    assert_eq!(
+        baseline.worst_regression(&current),
+        None,
+        "Nothing got slower"
+    );
+
+    let mut empty = AccVec::<true, u64, u32>::with_capacity(3);
+    assert_eq!(
+        empty.worst_regression(&baseline),
+        None,
+        "Zero counts ignored"
+    );
+    empty.set_label(0, "b");
+    empty.record(250);
+    assert_eq!(
+        empty.worst_regression(&baseline),
+        None,
+        "Nothing to compare 250 with"
+    );
+}
+
+#[test]
+fn take_and_snapshot() {
+    let mut v = AccVec::<true, u64, u32>::with_capacity(2);
+    v.start();
+    v.acc_push();
+    v.acc_push();
+    v.start();
+    v.acc_push();
+    let taken = v.take();
+    assert_eq!(taken.len(), 1, "Only up to the last pushed");
+    assert_eq!(taken[0].1, 2);
+    assert!(v.acc_cnts().is_empty());
+    assert_eq!(v.all_acc_cnts().len(), 2);
+    assert_eq!(v.all_acc_cnts()[0], (0, 0), "Taken entry is reset");
+    assert_eq!(v.all_acc_cnts()[1].1, 1, "Later entry is kept");
+    assert!(v.take().is_empty());
+
+    // With acc_n nothing is pushed, so nothing is taken or lost
+    let mut v = AccVec::<true, u64, u32>::with_capacity(2);
+    v.start();
+    v.acc_n(0);
+    v.acc_n(1);
+    assert!(v.take().is_empty());
+    assert_eq!(v.all_acc_cnts()[0].1, 1);
+    assert_eq!(v.all_acc_cnts()[1].1, 1);
+
+    let mut a = AccArray::<true, u64, u32, 2>::default();
+    a.start();
+    a.acc_n(1);
+    let (accs, cnts) = a.snapshot_and_clear();
+    assert_eq!(cnts, [0, 1]);
+    assert_eq!(accs[0], 0);
+    assert!(a.is_empty());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn write_csv() {
+    let a = AccArray::<true, u64, u32, 3>::with_values([10, 0, 9], [4, 0, 3]);
+    let mut csv = vec![];
+    a.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "index,sum,count,avg\n0,10,4,2.5\n1,0,0,\n2,9,3,3\n"
+    );
+
+    let mut v = AccVec::<true, u64, ()>::default();
+    v.record(7);
+    v.record(8);
+    let mut csv = vec![];
+    v.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "index,sum,count,avg\n0,7,,\n1,8,,\n",
+        "No count or average for a count of ()"
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn to_json() {
+    let a = AccArray::<true, u64, u32, 3>::with_values([10, 0, 9], [4, 0, 3]);
+    let json = a.to_json();
+    assert_eq!(
+        json,
+        r#"{"slots":[{"index":0,"sum":10,"count":4,"avg":2.5},{"index":1,"sum":0,"count":0,"avg":null},{"index":2,"sum":9,"count":3,"avg":3}]}"#
+    );
+    let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(v["slots"][2]["avg"], 3.0);
+
+    let mut v = AccVec::<true, u64, ()>::default();
+    v.record(7);
+    assert_eq!(
+        v.to_json(),
+        r#"{"slots":[{"index":0,"sum":7,"count":null,"avg":null}]}"#,
+        "No count or average for a count of ()"
+    );
+    assert_eq!(
+        AccVec::<true, u64, u32>::default().to_json(),
+        r#"{"slots":[]}"#
+    );
+}
+
+#[test]
+fn float_compensated() {
+    let mut a = AccArray::<true, f32, u32, 2>::with_values([1.0e8, 0.0], [0, 0]);
+    assert_eq!(1.0e8_f32 + 1.0, 1.0e8, "Naive f32 addition of 1 is lost");
+    for _ in 0..1000 {
+        a.acc_n_span(0, 0, 1);
+        a.acc_n_span(1, 0, 3);
+    }
+    assert_eq!(a.accs(), &[100_001_000.0, 3000.0], "Compensated sum");
+    assert_eq!(a.cnts(), &[1000, 1000]);
+    a.clear();
+    a.acc_n_span(0, 0, 5);
+    assert_eq!(a.accs(), &[5.0, 0.0], "Compensation is reset by clear");
+}
+
+/// A fake counter, that advances by 1 tick on every read
+struct Ones;
+static ONES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+impl CustomCounter for Ones {
+    fn now() -> u64 {
+        ONES.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn float_compensated_checked() {
+    let mut a = AccArray::<true, f32, u32, 1, CustomArch<Ones>>::with_values([1.0e8], [0]);
+    for _ in 0..1000 {
+        a.start();
+        assert!(a.acc_n_checked(0), "A float accumulator does not saturate");
+        a.acc_n_span(0, 0, 1);
+    }
+    assert_eq!(a.accs(), &[100_002_000.0], "Compensated sum");
+    assert_eq!(a.cnts(), &[2000]);
+}
+
+#[test]
+fn float_compensated_merge() {
+    let mut a = AccArray::<true, f32, u32, 1>::with_values([1.0e8], [0]);
+    let mut b = AccArray::<true, f32, u32, 1>::with_values([3.0], [0]);
+    for _ in 0..1001 {
+        a.acc_n_span(0, 0, 1);
+        b.acc_n_span(0, 0, 1);
+    }
+    a.merge(&b);
+    for _ in 0..1000 {
+        a.acc_n_span(0, 0, 1);
+    }
+    assert_eq!(a.accs(), &[100_003_005.0], "Compensated merge");
+    assert_eq!(a.cnts(), &[3002]);
+}
+
+/// Fake counters that are only advanced explicitly, by [step]; each
+/// test using them has its own, as the tests run in parallel
+struct Stepped<const I: usize>;
+static STEPPED: [std::sync::atomic::AtomicU64; 3] =
+    [const { std::sync::atomic::AtomicU64::new(0) }; 3];
+impl<const I: usize> CustomCounter for Stepped<I> {
+    fn now() -> u64 {
+        STEPPED[I].load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+fn step<const I: usize>(ticks: u64) {
+    STEPPED[I].fetch_add(ticks, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[test]
+fn float_compensated_2d() {
+    let mut a = AccArray2D::<true, f32, u32, 2, 1, CustomArch<Stepped<0>>>::default();
+    a.start();
+    step::<0>(100_000_000);
+    a.acc_rc(1, 0);
+    a.acc_rc_restart(0, 0);
+    for _ in 0..1000 {
+        a.start();
+        step::<0>(1);
+        a.acc_rc(1, 0);
+        a.acc_rc_restart(0, 0);
+    }
+    assert_eq!(
+        a.accs(),
+        &[[100_001_000.0], [100_001_000.0]],
+        "Compensated sums"
+    );
+    assert_eq!(a.cnts(), &[[1001], [1001]]);
+}
+
+#[test]
+fn float_compensated_trace() {
+    let mut t = AccTrace::<true, f32, 1, CustomArch<Stepped<1>>>::default();
+    t.start();
+    step::<1>(100_000_000);
+    t.next();
+    t.acc();
+    for _ in 0..1000 {
+        t.start();
+        step::<1>(1);
+        t.next();
+        t.acc();
+    }
+    assert_eq!(t.acc_trace(), &[100_001_000.0], "Compensated sum");
+    assert_eq!(t.iterations(), 1001);
+    t.clear();
+    t.start();
+    step::<1>(5);
+    t.next();
+    t.acc();
+    assert_eq!(t.acc_trace(), &[5.0], "Compensation is reset by clear");
+}
+
+#[test]
+fn float_compensated_vec() {
+    let mut a = AccVec::<true, f32, u32, CustomArch<Stepped<2>>>::with_capacity(1);
+    let mut b = AccVec::<true, f32, u32, CustomArch<Stepped<2>>>::with_capacity(1);
+    a.start();
+    step::<2>(100_000_000);
+    a.acc_n_restart(0);
+    for _ in 0..1001 {
+        step::<2>(1);
+        a.acc_n_restart(0);
+    }
+    b.start();
+    step::<2>(3);
+    b.acc_n_restart(0);
+    for _ in 0..1001 {
+        step::<2>(1);
+        b.acc_n_restart(0);
+    }
+    a.merge(&b);
+    for _ in 0..1000 {
+        a.start();
+        step::<2>(1);
+        a.acc_n(0);
+    }
+    assert_eq!(
+        a.all_acc_cnts(),
+        &[(100_003_005.0, 3004)],
+        "Compensated sum and merge"
+    );
+    a.clear();
+    a.record(5);
+    assert_eq!(a.acc_cnts(), &[(5.0, 1)], "Compensation is reset by clear");
+}
+
+#[test]
+fn diff() {
+    let mut a = AccArray::<true, u64, u32, 3>::default();
+    a.set_labels(&["a", "b", "c"]);
+    a.set_outlier_threshold(100);
+    a.acc_n_span(0, 0, 10);
+    a.acc_n_span(1, 0, 20);
+    let t1 = a;
+    a.acc_n_span(0, 0, 5);
+    a.acc_n_span(2, 0, 7);
+    a.acc_n_span(2, 0, 8);
+    a.acc_n_span(2, 0, 1000);
+    let t2 = a;
+    let d = t2.diff(&t1);
+    assert_eq!(d.accs(), &[5, 0, 15], "Activity between the snapshots");
+    assert_eq!(d.cnts(), &[1, 0, 2]);
+    assert_eq!(d.rejected_count(), 1);
+    assert_eq!(d.label(2), Some("c"));
+
+    let mut merged = t1;
+    merged.merge(&d);
+    assert_eq!(
+        merged.accs(),
+        t2.accs(),
+        "Earlier plus the diff is the later"
+    );
+    assert_eq!(merged.cnts(), t2.cnts());
+
+    let back = t1.diff(&t2);
+    assert_eq!(back.accs(), &[0, 0, 0], "Differences saturate at zero");
+    assert_eq!(back.cnts(), &[0, 0, 0]);
+}
@@ -0,0 +1,54 @@
+//a Imports
+use std::time::Duration;
+
+use cpu_timer::{counter_frequency, uses_asm, Calibration};
+
+//a Tests
+//fp calibrate_steady
+#[test]
+fn calibrate_steady() {
+    for gap in [Duration::ZERO, Duration::from_millis(2)] {
+        let c = Calibration::calibrate_steady(Duration::from_millis(20), gap);
+        let tpn = c.ticks_per_nanosecond();
+        dbg!(gap, tpn);
+        assert!(tpn > 0.0, "Counter must advance");
+        if !uses_asm::<true>() && !cfg!(feature = "cpu-time") {
+            assert!((tpn - 1.0).abs() < 0.1, "std::time ticks are nanoseconds");
+        }
+    }
+}
+
+//fp measure
+#[test]
+fn measure() {
+    let c = Calibration::measure(Duration::from_millis(10));
+    let tpn = c.ticks_per_nanosecond();
+    dbg!(tpn);
+    assert!(tpn > 0.0, "Counter must advance");
+    if !uses_asm::<true>() {
+        assert_eq!(tpn, 1.0);
+    }
+    let ticks = (tpn * 1_000_000.0) as u64;
+    let d = c.ticks_to_duration(ticks);
+    assert!(d.abs_diff(Duration::from_millis(1)) < Duration::from_micros(1));
+    assert!((c.ticks_to_nanos(ticks) - 1_000_000.0).abs() < 1000.0);
+}
+
+//fp hz
+#[test]
+fn hz() {
+    let c = Calibration::measure(Duration::from_millis(10));
+    assert_eq!(c.hz(), c.ticks_per_nanosecond() * 1e9);
+    if !uses_asm::<true>() {
+        assert_eq!(counter_frequency(), Some(1_000_000_000));
+    }
+    if let Some(freq) = counter_frequency() {
+        let ratio = c.hz() / freq as f64;
+        dbg!(freq, c.hz());
+        assert!(
+            (0.9..1.1).contains(&ratio),
+            "Calibrated {}Hz should be near the queried {freq}Hz",
+            c.hz()
+        );
+    }
+}
@@ -1,7 +1,19 @@
 //a Imports
+#![allow(
+    clippy::unused_unit,
+    clippy::cast_abs_to_unsigned,
+    clippy::question_mark,
+    clippy::identity_op,
+    clippy::needless_range_loop,
+    clippy::println_empty_string
+)]
 use std::collections::HashMap;
 
-use cpu_timer::{AccTimer, AccTrace, DeltaTimer, TArch, TDesc, Timer, Trace};
+use cpu_timer::{
+    anchor, probe_asm_timer, start_all, tsc_is_invariant, uses_asm, AccTimer, AccTrace,
+    AtomicAccTimer, DeltaTimer, DynTimer, MinTimer, NamedTrace, Nanos, PairTimer, RecordDelta,
+    Startable, TArch, TDesc, Ticks, Timer, TimerError, Trace,
+};
 
 //a Work functions
 //fp do_work
@@ -57,6 +69,18 @@ fn abs_diff(a: u64, b: u64) -> u64 {
     ((a as i64) - (b as i64)).abs() as u64
 }
 
+//fp spin_ticks
+/// Busy-wait until the timer's own clock has advanced by *ticks*, so
+/// that the time is seen whatever the clock (such as the thread CPU
+/// time with the `cpu-time` feature) and however loaded the machine
+fn spin_ticks<const S: bool>(ticks: u64)
+where
+    TDesc<S>: TArch,
+{
+    let t0 = cpu_timer::raw::now::<S>();
+    while cpu_timer::raw::now::<S>().wrapping_sub(t0) < ticks {}
+}
+
 //fp check_data
 fn check_data(data: &[u32]) -> Result<(), String> {
     let n = data.len() as u64;
@@ -144,6 +168,11 @@ where
     for _retries in 0..10 {
         let mut t0 = Trace::<S, u32, 16>::default();
         trace_work(&mut t0);
+        assert_eq!(
+            t0.filled().collect::<Vec<_>>(),
+            t0.trace(),
+            "All 16 steps are filled"
+        );
         let mut samples: Vec<u32> = vec![];
         for (i, t) in t0.trace().iter().enumerate() {
             samples.push(t / (i + 1) as u32);
@@ -156,6 +185,42 @@ where
     result
 }
 
+//fp generic_test_trace_filled
+fn generic_test_trace_filled<const S: bool>()
+where
+    TDesc<S>: TArch,
+{
+    let mut t0 = Trace::<S, u32, 4>::default();
+    t0.start();
+    assert_eq!(t0.filled().len(), 0);
+    assert_eq!(t0.max_step(), None);
+    assert_eq!(t0.min_step(), None);
+    do_work::<S>();
+    t0.next();
+    do_work::<S>();
+    t0.next();
+    let filled: Vec<u32> = t0.filled().collect();
+    assert_eq!(filled, &t0.trace()[0..2]);
+    let (max_i, max) = t0.max_step().unwrap();
+    let (min_i, min) = t0.min_step().unwrap();
+    assert!(min <= max);
+    assert_eq!(max, filled[max_i] as u64);
+    assert_eq!(min, filled[min_i] as u64);
+
+    let s = t0.sparkline();
+    assert_eq!(s.chars().count(), 2, "One block per filled step");
+    if filled.iter().any(|t| *t > 0) {
+        assert!(s.contains('█'), "Largest step is a full block");
+    }
+
+    let v = t0.to_acc_vec();
+    assert_eq!(v.acc_cnts().len(), 2);
+    for (i, (t, c)) in v.acc_cnts().iter().enumerate() {
+        assert_eq!(*t, t0.trace()[i]);
+        assert_eq!(*c, 1);
+    }
+}
+
 //fp generic_test_acc_trace
 fn generic_test_acc_trace<const S: bool>() -> Result<(), String>
 where
@@ -179,7 +244,20 @@ where
     }
     for _retries in 0..10 {
         let mut t0 = AccTrace::<S, u32, 16>::default();
+        assert!(t0.is_empty());
         acc_trace_work(&mut t0);
+        assert!(!t0.is_empty());
+        assert_eq!(t0.iterations(), 10);
+        let (slowest, acc) = t0.slowest_step().unwrap();
+        assert_eq!(acc, t0.acc_trace()[slowest] as u64);
+        assert!(t0.acc_trace().iter().all(|a| (*a as u64) <= acc));
+        let (avg_slowest, avg) = t0.slowest_step_avg().unwrap();
+        assert_eq!(avg_slowest, slowest);
+        assert_eq!(avg, acc as f64 / 10.0);
+        let pcts = t0.as_percentages();
+        let total: f64 = pcts.iter().sum();
+        assert!((total - 100.0).abs() < 1e-6, "Percentages sum to 100");
+        assert!(pcts.iter().all(|p| *p <= pcts[slowest]));
         let mut samples: Vec<u32> = vec![];
         for (i, t) in t0.last_trace().iter().enumerate() {
             samples.push(t / (i + 1) as u32);
@@ -274,6 +352,30 @@ where
     // assert!(false);
 }
 
+//fp generic_test_anchor
+fn generic_test_anchor<const S: bool>()
+where
+    TDesc<S>: TArch,
+{
+    let a = anchor::<S>();
+    let mut t0 = Trace::<S, u32, 1>::default();
+    let mut t1 = AccTrace::<S, u32, 1>::default();
+    t0.start();
+    do_work::<S>();
+    t1.start();
+    let o0 = t0.start_offset_from(a);
+    let o1 = t1.start_offset_from(a);
+    dbg!(o0, o1);
+    assert!(
+        o0 < o1,
+        "Trace started first should have the smaller offset"
+    );
+    assert!(
+        o1 - o0 < 1 << 40,
+        "Offsets should not have wrapped, as both traces started after the anchor"
+    );
+}
+
 //fp generic_test_timer_values
 fn generic_test_timer_values<const S: bool>()
 where
@@ -350,6 +452,132 @@ fn test_trace() -> Result<(), String> {
     Ok(())
 }
 
+//fp test_trace_filled
+#[test]
+fn test_trace_filled() {
+    generic_test_trace_filled::<true>();
+    generic_test_trace_filled::<false>();
+}
+
+//fp test_trace_verify
+#[test]
+fn test_trace_verify() {
+    let mut t = Trace::<true, u32, 2>::default();
+    t.start();
+    t.next();
+    assert!(!t.is_complete());
+    t.next();
+    assert!(t.is_complete());
+    t.verify();
+}
+
+//fp test_delta_timer_pause
+#[test]
+fn test_delta_timer_pause() {
+    let mut t = DeltaTimer::<true>::default();
+    let now = cpu_timer::raw::now::<true>();
+    t.start_at(now.wrapping_sub(1_000_000));
+    t.pause();
+    assert!(t.is_paused());
+    t.stop();
+    let paused = t.value();
+    assert!(paused >= 1_000_000);
+    t.pause();
+    t.stop();
+    assert_eq!(t.value(), paused, "Paused time is not counted");
+    t.resume();
+    assert!(!t.is_paused());
+    t.stop();
+    assert!(t.value() >= paused, "Resumed segment is added");
+    t.start();
+    t.stop();
+    assert!(t.value() < paused, "Start clears the accumulated time");
+
+    let mut t = DeltaTimer::<false>::default();
+    t.start();
+    t.pause();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    t.resume();
+    t.stop();
+    assert!(
+        t.value() < 10_000_000,
+        "Sleep while paused is excluded, got {}ns",
+        t.value()
+    );
+}
+
+//fp test_trace_narrows_on_read
+#[test]
+fn test_trace_narrows_on_read() {
+    let mut t = Trace::<true, u8, 2>::default();
+    let now = cpu_timer::raw::now::<true>();
+    t.start_at(now.wrapping_sub(1_000));
+    t.next();
+    assert_eq!(t.trace()[0], 255, "A long step saturates when read");
+    assert_eq!(t.filled().collect::<Vec<_>>(), [255]);
+    let (i, max) = t.max_step().unwrap();
+    assert_eq!(i, 0);
+    assert!(max >= 1_000, "The full precision delta is retained");
+}
+
+//fp test_stop_checked
+#[test]
+fn test_stop_checked() {
+    let mut t = DeltaTimer::<true>::default();
+    t.start();
+    let d = t.stop_checked().unwrap();
+    assert_eq!(d, t.value());
+
+    // A start in the future looks like a wrapped counter
+    let now = cpu_timer::raw::now::<true>();
+    t.start_at(now.wrapping_add(1 << 40));
+    assert_eq!(t.stop_checked(), Err(TimerError::Wraparound));
+    assert_eq!(t.value(), d, "Delta unchanged on an error");
+    assert!(TimerError::Wraparound.to_string().contains("wrapped"));
+}
+
+//fp test_delta_timer_stop_unstarted
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "stopped without having been started")]
+fn test_delta_timer_stop_unstarted() {
+    let mut t = DeltaTimer::<true>::default();
+    t.stop();
+}
+
+//fp test_named_trace
+#[test]
+fn test_named_trace() {
+    let mut t = NamedTrace::<true, u32, 3>::new(["stage_a", "stage_b", "stage_c"]);
+    t.start();
+    t.next();
+    t.next();
+    t.next();
+    assert!(t.trace().is_complete());
+    let labeled = t.trace_labeled();
+    assert_eq!(labeled.len(), 3);
+    assert_eq!(labeled[1].0, "stage_b");
+    assert_eq!(labeled[1].1, t.trace().trace()[1]);
+    let s = format!("{t}");
+    assert!(s.starts_with("stage_a: "), "{s}");
+    assert_eq!(s.matches(", ").count(), 2, "{s}");
+    assert_eq!(
+        t.trace().trace_labeled(&["a", "b", "c"])[2],
+        ("c", t.trace().trace()[2])
+    );
+}
+
+//fp test_trace_verify_incomplete
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "is a call to 'next' missing?")]
+fn test_trace_verify_incomplete() {
+    let mut t = Trace::<true, u32, 3>::default();
+    t.start();
+    t.next();
+    t.verify();
+}
+
 //fp test_acc_trace
 #[test]
 fn test_acc_trace() -> Result<(), String> {
@@ -371,3 +599,574 @@ fn test_timer_values() {
     generic_test_timer_values::<true>();
     generic_test_timer_values::<false>();
 }
+
+//fp test_anchor
+#[test]
+fn test_anchor() {
+    generic_test_anchor::<true>();
+    generic_test_anchor::<false>();
+}
+
+//fp test_uses_asm
+#[test]
+fn test_uses_asm() {
+    assert!(!uses_asm::<false>(), "std::time is never asm");
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    assert!(uses_asm::<true>(), "This architecture has an asm timer");
+    assert_eq!(probe_asm_timer(), uses_asm::<true>());
+}
+
+//fp test_x86_timer
+#[cfg(target_arch = "x86")]
+#[test]
+fn test_x86_timer() {
+    let mut increasing = 0;
+    for _ in 0..100 {
+        let t0 = anchor::<true>();
+        let t1 = anchor::<true>();
+        if t1 >= t0 {
+            increasing += 1;
+        }
+    }
+    assert!(increasing > 90, "The counter should mostly increase");
+}
+
+//fp test_cpu_time
+#[cfg(all(feature = "cpu-time", any(target_os = "linux", target_os = "macos")))]
+#[test]
+fn test_cpu_time() {
+    let mut t = DeltaTimer::<false>::default();
+    t.start();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    t.stop();
+    assert!(
+        t.value() < 20_000_000,
+        "Sleeping should consume (almost) no thread CPU time, got {}ns",
+        t.value()
+    );
+}
+
+//fp test_dyn_timer
+#[test]
+fn test_dyn_timer() {
+    fn time_it(t: &mut DynTimer) -> u64 {
+        t.start();
+        // Spin on the clock of the timer, so that this takes the time
+        // even with the `cpu-time` feature on a loaded machine
+        if t.is_asm() {
+            spin_ticks::<true>(1000);
+        } else {
+            spin_ticks::<false>(1_000_000);
+        }
+        t.elapsed_and_update()
+    }
+    let mut t = DynTimer::new(true);
+    assert!(t.is_asm());
+    assert!(time_it(&mut t) > 0);
+    let mut t = DynTimer::new(false);
+    assert!(!t.is_asm());
+    assert!(time_it(&mut t) >= 500_000, "1ms spin is at least 5e5ns");
+}
+
+//fp test_dyn_timer_non_decreasing
+#[test]
+fn test_dyn_timer_non_decreasing() {
+    for use_asm in [true, false] {
+        let mut t = DynTimer::new(use_asm);
+        assert_eq!(t.is_asm(), use_asm);
+        t.start();
+        let mut last = 0;
+        for _ in 0..100 {
+            let e = t.elapsed();
+            assert!(e >= last, "Elapsed {e} decreased from {last}");
+            last = e;
+        }
+        t.stop();
+        assert!(
+            t.value() >= last,
+            "Value {} below elapsed {last}",
+            t.value()
+        );
+    }
+}
+
+//fp test_acc_timer_outlier
+#[test]
+fn test_acc_timer_outlier() {
+    let mut t = AccTimer::<false>::default();
+    t.set_outlier_threshold(1_000_000);
+    t.start();
+    t.stop();
+    let acc = t.acc_value();
+    t.start();
+    let now = std::time::Instant::now();
+    while now.elapsed() < std::time::Duration::from_millis(2) {}
+    t.stop();
+    assert_eq!(t.acc_value(), acc, "2ms busy-wait is not accumulated");
+    assert_eq!(t.rejected_count(), 1);
+    assert!(t.last_delta() > 1_000_000);
+}
+
+//fp test_acc_timer_variance
+#[test]
+fn test_acc_timer_variance() {
+    let mut t = AccTimer::<false>::default();
+    assert!(t.is_empty());
+    assert_eq!((t.mean(), t.variance(), t.std_dev()), (0.0, 0.0, 0.0));
+    for us in [100, 300] {
+        t.start();
+        let now = std::time::Instant::now();
+        while now.elapsed() < std::time::Duration::from_micros(us) {}
+        t.stop();
+    }
+    let mean = t.mean();
+    assert_eq!(mean, t.acc_value() as f64 / 2.0);
+    let d = t.last_delta() as f64 - mean;
+    assert!(
+        (t.variance() - d * d).abs() <= 1.0 + d * d * 1e-9,
+        "Two samples have variance ((a-b)/2)^2"
+    );
+    assert!(t.std_dev() > 50_000.0, "The deltas differ by about 200us");
+    t.clear();
+    assert_eq!(t.variance(), 0.0);
+    assert!(t.is_empty());
+}
+
+//fp test_clear_last
+#[test]
+fn test_clear_last() {
+    let mut t = AccTimer::<false>::default();
+    t.start();
+    let now = std::time::Instant::now();
+    while now.elapsed() < std::time::Duration::from_micros(100) {}
+    t.stop();
+    let acc = t.acc_value();
+    assert!(t.last_delta() > 0);
+    t.clear_last();
+    assert_eq!(t.last_delta(), 0);
+    assert_eq!(t.acc_value(), acc, "Accumulator is untouched");
+    assert_eq!(t.count(), 1);
+}
+
+//fp test_acc_timer_overhead
+#[test]
+fn test_acc_timer_overhead() {
+    let mut t = AccTimer::<true>::default();
+    assert_eq!(t.overhead_applied(), 0);
+    assert!(!t.to_string().contains("overhead"));
+    t.set_overhead(u64::MAX);
+    t.start();
+    t.stop();
+    assert_eq!(t.acc_value(), 0, "Compensation saturates at zero");
+    assert_eq!(t.count(), 1);
+    t.set_overhead(14);
+    t.clear();
+    assert_eq!(t.overhead_applied(), 14, "Clear keeps the overhead");
+    assert_eq!(t.to_string(), "(0, 0, -) [overhead -14/call]");
+}
+
+//fp test_acc_timer_stop_repeated
+#[test]
+fn test_acc_timer_stop_repeated() {
+    let mut t = AccTimer::<true>::default();
+    t.start_at(cpu_timer::raw::now::<true>().wrapping_sub(1_000_000));
+    t.stop_repeated(1000);
+    assert!((1000..2000).contains(&t.last_delta()), "{}", t.last_delta());
+    assert_eq!(t.acc_value(), t.last_delta());
+    assert_eq!(t.count(), 1, "Counted once, not per iteration");
+    t.start();
+    t.stop_repeated(0);
+    assert_eq!(t.count(), 2, "Zero repetitions treated as one");
+}
+
+//fp test_atomic_acc_timer
+#[test]
+fn test_atomic_acc_timer() {
+    let t = AtomicAccTimer::<false>::default();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..10 {
+                    let token = t.start();
+                    spin_ticks::<false>(10_000);
+                    t.stop(token);
+                }
+            });
+        }
+    });
+    assert_eq!(t.count(), 40);
+    assert!(t.acc_value() >= 40 * 10_000, "{}", t.acc_value());
+    t.clear();
+    assert_eq!(t.count(), 0);
+    assert_eq!(t.acc_value(), 0);
+}
+
+//fp test_measure_overhead
+#[test]
+fn test_measure_overhead() {
+    assert_eq!(Timer::<true>::measure_overhead(0), 0);
+    let overhead = Timer::<true>::measure_overhead(1000);
+    assert!(overhead < 10_000, "Overhead {overhead} is implausible");
+    let mut t = DeltaTimer::<true>::default();
+    t.start();
+    t.stop();
+    assert_eq!(t.value_compensated(0), t.value());
+    assert_eq!(t.value_compensated(u64::MAX), 0, "Saturates at zero");
+}
+
+//fp test_acc_trace_saturates
+#[test]
+fn test_acc_trace_saturates() {
+    let mut sat = AccTrace::<true, u8, 1>::default();
+    let mut wrap = AccTrace::<true, u8, 1>::default();
+    let mut expected = 0_u8;
+    for _ in 0..3 {
+        let now = cpu_timer::raw::now::<true>();
+        sat.start_at(now.wrapping_sub(100));
+        sat.next();
+        sat.acc();
+        wrap.start_at(now.wrapping_sub(100));
+        wrap.next();
+        wrap.wrapping_acc();
+        expected = expected.wrapping_add(wrap.last_trace()[0]);
+    }
+    assert_eq!(sat.acc_trace(), &[u8::MAX], "Saturated");
+    assert_eq!(wrap.acc_trace(), &[expected], "Wrapped");
+    assert_eq!(wrap.iterations(), 3);
+}
+
+//fp test_acc_timer_ewma
+#[test]
+fn test_acc_timer_ewma() {
+    let mut t = AccTimer::<true>::default();
+    assert_eq!(t.alpha(), 1.0);
+    assert_eq!(t.ewma(), 0.0);
+    for d in [100, 300] {
+        t.record(d);
+        assert_eq!(t.ewma(), d as f64, "Alpha of 1 tracks the last delta");
+    }
+
+    let mut t = AccTimer::<true>::with_alpha(0.5);
+    t.set_overhead(10);
+    t.set_outlier_threshold(1000);
+    t.record(110);
+    assert_eq!(t.ewma(), 100.0, "Seeded with the first delta");
+    t.record(310);
+    assert_eq!(t.ewma(), 200.0);
+    t.record(5000);
+    assert_eq!(t.ewma(), 200.0, "Outliers are not included");
+    t.record(10);
+    assert_eq!(t.ewma(), 100.0);
+    assert_eq!(t.acc_value(), 400, "The plain sum is still kept");
+    t.clear();
+    assert_eq!(t.ewma(), 0.0);
+    assert_eq!(t.alpha(), 0.5, "Alpha is kept over a clear");
+}
+
+//fp test_min_timer
+#[test]
+fn test_min_timer() {
+    let mut t = MinTimer::<true>::default();
+    assert_eq!(t.min_value(), u64::MAX, "No deltas yet");
+    let now = cpu_timer::raw::now::<true>();
+    t.start_at(now.wrapping_sub(1_000_000));
+    t.stop();
+    let long = t.min_value();
+    assert!(long >= 1_000_000);
+    t.start();
+    t.stop();
+    assert!(t.min_value() < long, "The minimum is kept");
+    t.start_at(now.wrapping_sub(2_000_000));
+    t.stop();
+    assert!(t.min_value() < long, "A longer delta does not change it");
+    assert_eq!(t.count(), 3);
+    t.clear();
+    assert_eq!(t.min_value(), u64::MAX);
+    assert_eq!(t.count(), 0);
+}
+
+//fp test_acc_timer_stop_if_below
+#[test]
+fn test_acc_timer_stop_if_below() {
+    let mut t = AccTimer::<true>::default();
+    let now = cpu_timer::raw::now::<true>();
+    t.start_at(now.wrapping_sub(1_000_000));
+    assert!(!t.stop_if_below(1000), "A delta of 1e6 is not below 1000");
+    assert_eq!(t.rejected_count(), 1);
+    assert_eq!(t.count(), 0);
+    assert_eq!(t.acc_value(), 0);
+    t.start_at(now.wrapping_sub(1_000_000));
+    assert!(t.stop_if_below(u64::MAX));
+    assert_eq!(t.count(), 1);
+    assert!(t.acc_value() >= 1_000_000);
+    t.set_outlier_threshold(10);
+    t.start_at(now.wrapping_sub(1_000_000));
+    assert!(
+        !t.stop_if_below(u64::MAX),
+        "Outlier threshold still applies"
+    );
+    assert_eq!(t.rejected_count(), 2);
+}
+
+//fp test_value_units
+#[test]
+fn test_value_units() {
+    let mut t = DeltaTimer::<true>::default();
+    t.start();
+    t.stop();
+    assert_eq!(t.value_ticks(), t.value());
+    let mut t = DeltaTimer::<false>::default();
+    t.start();
+    spin_ticks::<false>(1_000_000);
+    t.stop();
+    assert_eq!(t.value_nanos(), t.value());
+    assert!(t.elapsed_duration() >= std::time::Duration::from_millis(1));
+    let mut t = Timer::<false>::default();
+    t.start();
+    spin_ticks::<false>(1_000_000);
+    assert!(t.elapsed_duration() >= std::time::Duration::from_millis(1));
+}
+
+//fp generic_test_start_all
+fn generic_test_start_all<const S: bool>()
+where
+    TDesc<S>: TArch,
+{
+    let a = anchor::<S>();
+    let mut t0 = Trace::<S, u32, 1>::default();
+    let mut t1 = AccTrace::<S, u32, 1>::default();
+    let mut t2 = AccTimer::<S>::default();
+    t0.start();
+    t1.start();
+    do_work::<S>();
+    start_all::<S>(&mut [&mut t0, &mut t1, &mut t2]);
+    let o0 = t0.start_offset_from(a);
+    assert_eq!(o0, t1.start_offset_from(a), "Traces have the same start");
+    assert!(o0 < 1 << 40, "Start is after the anchor");
+    t2.stop();
+    assert!(t2.acc_value() < 1 << 40, "Start is before the stop");
+}
+
+//fp test_start_all
+#[test]
+fn test_start_all() {
+    generic_test_start_all::<true>();
+    generic_test_start_all::<false>();
+}
+
+//fp test_elapsed_u32_saturating
+#[test]
+fn test_elapsed_u32_saturating() {
+    let mut t = Timer::<true>::default();
+    t.start();
+    assert!(u64::from(t.elapsed_u32_saturating()) <= t.elapsed());
+    if uses_asm::<true>() {
+        t.set_start(anchor::<true>().wrapping_sub(1 << 40));
+        assert_eq!(t.elapsed_u32_saturating(), u32::MAX, "Saturates, not wraps");
+    }
+}
+
+//fp test_raw_now
+#[test]
+fn test_raw_now() {
+    use cpu_timer::raw;
+    let now = std::time::Instant::now();
+    let t0 = raw::now::<false>();
+    spin_ticks::<false>(1_000_000);
+    let dt = raw::now::<false>().wrapping_sub(t0);
+    assert!(
+        now.elapsed() >= std::time::Duration::from_millis(1),
+        "std::time ticks are nanoseconds"
+    );
+    assert!(dt < 10_000_000_000);
+    let t0 = raw::now::<true>();
+    assert!(raw::now::<true>().wrapping_sub(t0) < 1 << 40);
+}
+
+//fp test_set_start
+#[test]
+fn test_set_start() {
+    let a = anchor::<true>();
+    let now = std::time::Instant::now();
+    while now.elapsed() < std::time::Duration::from_millis(2) {}
+    let mut fresh = Timer::<true>::default();
+    fresh.start();
+    let mut t = Timer::<true>::default();
+    t.set_start(a);
+    let from_anchor = t.elapsed();
+    assert!(from_anchor >= fresh.elapsed());
+    assert!(from_anchor > 0);
+}
+
+//fp test_const_new
+#[cfg(any(
+    target_arch = "aarch64",
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "x86",
+    target_arch = "x86_64"
+))]
+#[test]
+fn test_const_new() {
+    const TIMER: Timer<true> = Timer::new();
+    const DELTA: DeltaTimer<true> = DeltaTimer::new();
+    static ACC: AccTimer<true> = AccTimer::new();
+    assert_eq!(DELTA.value(), 0);
+    assert_eq!(ACC.acc_value(), 0);
+    assert_eq!(ACC.count(), 0);
+    let mut t = TIMER;
+    t.start();
+    assert!(t.elapsed() < 1 << 40);
+}
+
+//fp test_scoped_timer
+#[test]
+fn test_scoped_timer() {
+    let mut t = DeltaTimer::<false>::default();
+    let f = |t: &mut DeltaTimer<false>, early: bool| -> Option<()> {
+        let _g = t.scope();
+        let now = std::time::Instant::now();
+        while now.elapsed() < std::time::Duration::from_micros(100) {}
+        if early {
+            return None;
+        }
+        Some(())
+    };
+    assert!(f(&mut t, true).is_none());
+    assert!(t.value() > 0, "Timer stopped on early return");
+    assert!(f(&mut t, false).is_some());
+    assert!(t.value() > 0);
+}
+
+//fp test_within
+#[test]
+fn test_within() {
+    use std::time::Duration;
+    let mut t = DeltaTimer::<false>::default();
+    t.start();
+    let now = std::time::Instant::now();
+    while now.elapsed() < Duration::from_millis(2) {}
+    t.stop();
+    assert!(t.within(Duration::from_secs(10)));
+    assert_eq!(t.exceeded_by(Duration::from_secs(10)), None);
+    assert!(!t.within(Duration::from_millis(1)));
+    let over = t.exceeded_by(Duration::from_millis(1)).unwrap();
+    assert_eq!(
+        over + Duration::from_millis(1),
+        Duration::from_nanos(t.value())
+    );
+}
+
+//fp test_try_new
+#[test]
+fn test_try_new() {
+    let t = DeltaTimer::<true>::try_new();
+    dbg!(tsc_is_invariant());
+    if !uses_asm::<true>() || tsc_is_invariant() {
+        assert!(t.is_ok());
+    } else {
+        assert_eq!(t.unwrap_err(), TimerError::NonInvariantCounter);
+    }
+}
+
+//fp test_elapsed_f64
+#[test]
+fn test_elapsed_f64() {
+    let mut t = Timer::<false>::default();
+    t.start();
+    let a = t.elapsed();
+    let b = t.elapsed_f64();
+    assert!(b >= a as f64, "Time does not go backwards");
+}
+
+//fp test_pair_timer
+#[test]
+fn test_pair_timer() {
+    let mut p = PairTimer::<false>::new("sleep", "spin");
+    p.a().start();
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    p.a().stop();
+    p.b().start();
+    p.b().stop();
+    let (a, b, ratio) = p.compare();
+    assert_eq!(ratio, b as f64 / a as f64);
+    assert_eq!(p.names(), ("sleep", "spin"));
+    let s = format!("{p}");
+    assert!(s.starts_with(&format!("sleep: {a} ticks, spin: {b} ticks (ratio ")));
+}
+
+//fp test_acc_duration
+#[test]
+fn test_acc_duration() {
+    use std::time::Duration;
+    let mut t = AccTimer::<false>::default();
+    assert_eq!(t.average_duration(), Duration::ZERO);
+    for _ in 0..4 {
+        t.start();
+        do_work::<false>();
+        t.stop();
+    }
+    assert_eq!(t.count(), 4);
+    assert_eq!(t.acc_duration(), Duration::from_nanos(t.acc_value()));
+    assert_eq!(
+        t.average_duration(),
+        Duration::from_nanos(t.acc_value() / 4)
+    );
+    t.clear();
+    assert_eq!(t.count(), 0);
+}
+
+//fp test_units
+#[test]
+fn test_units() {
+    let t = Timer::<false>::default();
+    let n: Nanos = t.elapsed_unit();
+    assert_eq!(format!("{}", Nanos(5)), "5 ns");
+    assert_eq!(format!("{}", Ticks(5)), "5 ticks");
+    assert!(n.as_duration() < std::time::Duration::from_secs(10));
+    let mut d = DeltaTimer::<true>::default();
+    d.start();
+    d.stop();
+    assert_eq!(u64::from(d.value_unit()), d.value());
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "x86_64"
+    ))]
+    let _: Ticks = d.value_unit();
+}
+
+//fp test_backwards_instant
+#[cfg(not(any(feature = "cpu-time", feature = "vdso")))]
+#[test]
+fn test_backwards_instant() {
+    // Starting in the future simulates the clock going backwards
+    let mut t = AccTimer::<false>::default();
+    t.start_at(anchor::<false>() + 1_000_000_000_000);
+    t.stop();
+    assert_eq!(t.last_delta(), 0, "Backwards std::time gives a zero delta");
+}
+
+//fp test_topology
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[test]
+fn test_topology() {
+    use cpu_timer::topology::{physical_core, read_with_processor, same_core};
+    assert!(same_core(0, 0));
+    if let Some((t0, p0)) = read_with_processor() {
+        let (t1, _) = read_with_processor().unwrap();
+        assert!(t1.wrapping_sub(t0) < 1 << 40);
+        dbg!(p0, physical_core(p0));
+        assert!(physical_core(p0).is_some(), "/proc/cpuinfo has apicid");
+    }
+    assert_eq!(physical_core(u32::MAX), None);
+    assert!(!same_core(0, u32::MAX));
+}
@@ -0,0 +1,108 @@
+//a Imports
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use cpu_timer::{
+    start_all_arch, AccArray, AccArray2D, AccTimer, AccTrace, AccVec, CustomArch, CustomCounter,
+    DeltaTimer, Timer,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A fake counter, that advances by 10 ticks on every read
+struct Fake;
+impl CustomCounter for Fake {
+    fn now() -> u64 {
+        COUNTER.fetch_add(10, Ordering::Relaxed)
+    }
+}
+
+type Arch = CustomArch<Fake>;
+
+static SET: AtomicU64 = AtomicU64::new(0);
+
+/// A fake counter, that only changes when it is set by the test
+struct Set;
+impl CustomCounter for Set {
+    fn now() -> u64 {
+        SET.load(Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn custom_timer() {
+    let mut t = DeltaTimer::<true, Arch>::default();
+    t.start();
+    t.stop();
+    assert_eq!(
+        t.value(),
+        10,
+        "One read of the fake counter per start and stop"
+    );
+
+    let mut t = Timer::<true, Arch>::default();
+    t.start();
+    assert_eq!(t.elapsed(), 10);
+    assert_eq!(t.elapsed_and_update(), 20);
+    assert_eq!(t.elapsed(), 10);
+
+    let mut t = AccTimer::<true, Arch>::default();
+    t.start();
+    t.stop();
+    t.start();
+    t.stop();
+    assert_eq!(t.acc_value(), 20);
+    assert_eq!(t.count(), 2);
+
+    let mut a = AccArray::<true, u64, u32, 2, Arch>::default();
+    a.start();
+    a.acc_n_restart(1);
+    a.acc_n_restart(1);
+    assert_eq!(a.accs(), &[0, 20]);
+    assert_eq!(a.cnts(), &[0, 2]);
+
+    {
+        let _g = a.scope(0);
+    }
+    assert_eq!(a.accs(), &[10, 20]);
+    assert_eq!(a.cnts(), &[1, 2]);
+
+    let mut a = AccArray2D::<true, u64, u32, 2, 2, Arch>::default();
+    a.start();
+    a.acc_rc_restart(1, 0);
+    a.acc_rc(1, 1);
+    assert_eq!(a.accs(), &[[0, 0], [10, 10]]);
+
+    let mut t = AccTrace::<true, u32, 3, Arch>::default();
+    t.start();
+    t.next();
+    t.next();
+    t.acc();
+    assert_eq!(t.last_trace(), [10, 10, 0]);
+    assert_eq!(t.acc_trace(), &[10, 10, 0]);
+
+    let mut v = AccVec::<true, u64, u32, Arch>::default();
+    v.start();
+    v.acc_push_restart();
+    v.acc_push();
+    assert_eq!(v.acc_cnts(), &[(10, 1), (10, 1)]);
+}
+
+#[test]
+fn custom_start_all() {
+    type Arch = CustomArch<Set>;
+    let mut a = AccArray::<true, u64, u32, 2, Arch>::default();
+    let mut d = DeltaTimer::<true, Arch>::default();
+    let mut t = AccTimer::<true, Arch>::default();
+    let mut v = AccVec::<true, u64, u32, Arch>::default();
+    SET.store(100, Ordering::Relaxed);
+    start_all_arch::<true, Arch>(&mut [&mut a, &mut d, &mut t, &mut v]);
+    SET.store(130, Ordering::Relaxed);
+    a.acc_n(1);
+    d.stop();
+    t.stop();
+    v.acc_push();
+    assert_eq!(a.accs(), &[0, 30], "Started at the counter value of 100");
+    assert_eq!(d.value(), 30);
+    assert_eq!(t.acc_value(), 30);
+    assert_eq!(v.acc_cnts(), &[(30, 1)]);
+}
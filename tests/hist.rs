@@ -0,0 +1,188 @@
+//a Imports
+use cpu_timer::{AccArray, AccTimer, AdaptiveTimer, HistTimer, Measured, RecordDelta, SampleRing};
+
+#[test]
+fn hist_record() {
+    let mut h = HistTimer::<true, 8>::default();
+    assert_eq!(h.edges(), &[0, 1, 2, 4, 8, 16, 32, 64]);
+    assert_eq!(h.percentile(50.0), 0, "Empty histogram has 0 percentiles");
+    for d in [0, 1, 3, 3, 5, 100, 1000] {
+        h.record(d);
+    }
+    assert_eq!(h.buckets(), &[1, 1, 2, 1, 0, 0, 0, 2]);
+    assert_eq!(h.total(), 7);
+    assert_eq!(h.percentile(0.0), 0);
+    assert_eq!(h.percentile(100.0), 1000);
+    let p50 = h.percentile(50.0);
+    assert!(
+        (2..4).contains(&p50),
+        "Median {p50} should be in the 2..4 bucket"
+    );
+
+    h.clear();
+    assert_eq!(h.total(), 0);
+    assert_eq!(h.edges(), &[0, 1, 2, 4, 8, 16, 32, 64]);
+}
+
+#[test]
+fn hist_timer() {
+    let mut h = HistTimer::<true, 32>::default();
+    for _ in 0..100 {
+        h.start();
+        h.stop();
+    }
+    assert_eq!(h.total(), 100);
+    assert!(h.percentile(10.0) <= h.percentile(90.0));
+}
+
+#[test]
+fn adaptive() {
+    let mut t = AdaptiveTimer::<true>::with_threshold(10);
+    for d in 1..=10 {
+        t.record(d * 10);
+    }
+    assert!(!t.is_bucketed());
+    assert_eq!(t.samples().unwrap().len(), 10);
+    assert_eq!(t.percentile(50.0), 50, "Nearest-rank median of 10..=100");
+    assert_eq!(t.percentile(100.0), 100);
+
+    t.record(110);
+    assert!(t.is_bucketed(), "Exceeded the threshold");
+    assert!(t.samples().is_none());
+    assert_eq!(t.count(), 11);
+    let p50 = t.percentile(50.0);
+    assert!(
+        (32..=64).contains(&p50),
+        "Median {p50} estimated from buckets"
+    );
+    assert_eq!(t.percentile(100.0), 110);
+
+    t.clear();
+    assert!(!t.is_bucketed());
+    assert_eq!(t.count(), 0);
+}
+
+#[test]
+fn hist_auto() {
+    let mut h = HistTimer::<true, 4>::auto(8);
+    for d in [1000, 1100, 1200, 1300, 1400, 1500, 1600, 1700] {
+        assert!(h.is_warming_up());
+        assert_eq!(h.buckets(), &[0, 0, 0, 0]);
+        h.record(d);
+    }
+    assert!(!h.is_warming_up());
+    assert_eq!(h.edges(), &[1000, 1200, 1400, 1600], "Edges at quantiles");
+    assert_eq!(h.buckets(), &[2, 2, 2, 2], "Warmup samples re-binned");
+    h.record(5000);
+    assert_eq!(h.buckets(), &[2, 2, 2, 3]);
+    assert_eq!(h.total(), 9);
+
+    h.clear();
+    assert!(h.is_warming_up(), "Clear re-arms the warmup");
+    for d in [10, 20, 30, 40, 50, 60, 70, 80] {
+        h.record(d);
+    }
+    assert!(!h.is_warming_up());
+    assert_eq!(h.edges(), &[10, 30, 50, 70], "Edges set afresh");
+    assert_eq!(h.buckets(), &[2, 2, 2, 2]);
+}
+
+#[test]
+fn hist_cdf() {
+    let mut h = HistTimer::<true, 4>::default();
+    assert!(h.cdf().is_empty());
+    for d in [0, 1, 3, 3, 5, 100, 1000, 2] {
+        h.record(d);
+    }
+    assert_eq!(
+        h.cdf(),
+        vec![(1, 0.125), (2, 0.25), (4, 0.625), (1001, 1.0)]
+    );
+}
+
+#[test]
+fn sample_ring() {
+    let mut r = SampleRing::<true, 4>::default();
+    assert!(r.is_empty());
+    assert_eq!(r.percentiles(&[50.0]), vec![0]);
+    for d in [5, 1, 3] {
+        r.record(d);
+    }
+    assert_eq!(r.samples().collect::<Vec<_>>(), vec![5, 1, 3]);
+    for d in [7, 2, 9] {
+        r.record(d);
+    }
+    assert_eq!(r.len(), 4);
+    assert_eq!(r.samples().collect::<Vec<_>>(), vec![3, 7, 2, 9]);
+    assert_eq!(r.percentiles(&[0.0, 50.0, 100.0]), vec![2, 3, 9]);
+    r.clear();
+    assert_eq!(r.samples().count(), 0);
+}
+
+#[test]
+fn hist_log_scale() {
+    let mut h = HistTimer::<true, 4>::log_scale(0, 1000);
+    assert_eq!(h.edges(), &[1, 10, 100, 1000]);
+    let h2 = HistTimer::<true, 4>::log_scale(1, 2);
+    assert_eq!(h2.edges(), &[1, 2, 3, 4], "Edges always increase");
+    let h1 = HistTimer::<true, 1>::log_scale(10, 1000);
+    assert_eq!(h1.edges(), &[10], "A single edge is the minimum");
+    for _ in 0..10 {
+        h.record(10);
+        h.record(99);
+    }
+    assert_eq!(h.buckets(), &[0, 20, 0, 0]);
+    let p50 = h.percentile(50.0);
+    assert!(
+        (30..33).contains(&p50),
+        "Median {p50} is interpolated logarithmically in 10..100"
+    );
+}
+
+#[test]
+fn hist_with_edges() {
+    let mut h = HistTimer::<true, 3>::with_edges([10, 20, 40]);
+    assert_eq!(h.edges(), &[10, 20, 40]);
+    for d in [5, 10, 19, 20, 39, 40, 1000] {
+        h.record(d);
+    }
+    assert_eq!(
+        h.buckets(),
+        &[3, 2, 2],
+        "Below first edge counts in the first bucket"
+    );
+    assert_eq!(h.total(), 7);
+    let p = h.percentile(50.0);
+    assert!((20..40).contains(&p), "Median {p} in second bucket");
+}
+
+fn measure_into<R: RecordDelta<true>>(sink: R) {
+    let _m = Measured::into(sink);
+    std::hint::black_box(0);
+}
+
+#[test]
+fn measured() {
+    let mut t = AccTimer::<true>::default();
+    let mut h = HistTimer::<true, 8>::default();
+    let mut ad = AdaptiveTimer::<true>::default();
+    let mut r = SampleRing::<true, 4>::default();
+    let mut a = AccArray::<true, u64, u32, 3>::default();
+    for _ in 0..2 {
+        measure_into(&mut t);
+        measure_into(&mut h);
+        measure_into(&mut ad);
+        measure_into(&mut r);
+        measure_into(a.slot(1));
+    }
+    assert_eq!(t.count(), 2);
+    assert_eq!(h.total(), 2);
+    assert_eq!(r.len(), 2);
+    assert_eq!(a.cnts(), &[0, 2, 0]);
+    let mut slot = a.slot(5);
+    slot.record(10);
+    assert_eq!(a.cnts(), &[0, 2, 0], "Out of range index ignored");
+    RecordDelta::<true>::record(&mut t, 100);
+    assert_eq!(t.count(), 3);
+    assert_eq!(t.last_delta(), 100);
+}
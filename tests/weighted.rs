@@ -0,0 +1,28 @@
+//a Imports
+use cpu_timer::TimeWeightedAvg;
+
+/// Busy-wait for a number of milliseconds of the std::time clock of
+/// the timers (which is the thread CPU time with the `cpu-time`
+/// feature, which a sleep or a descheduled thread does not advance)
+fn spin_ms(ms: u64) {
+    let t0 = cpu_timer::raw::now::<false>();
+    while cpu_timer::raw::now::<false>().wrapping_sub(t0) < ms * 1_000_000 {}
+}
+
+#[test]
+fn time_weighted_avg() {
+    let mut q = TimeWeightedAvg::<false>::default();
+    q.set(7.0);
+    assert_eq!(q.ticks(), 0);
+    assert_eq!(q.average(), 7.0, "No time accumulated gives the value");
+    q.set(0.0);
+    q.clear();
+    q.set(0.0);
+    spin_ms(5);
+    q.set(10.0);
+    spin_ms(5);
+    q.set(0.0);
+    assert!(q.ticks() >= 10_000_000);
+    let avg = q.average();
+    assert!((3.0..7.0).contains(&avg), "Average {avg} should be near 5");
+}